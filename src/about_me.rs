@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct AboutMe {
+    #[serde(default)]
+    pub about_me: String,
+}
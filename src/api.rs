@@ -0,0 +1,468 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::LoadError,
+    export::markdown,
+    loader::parse_json,
+    resume::Resume,
+    settings::Settings,
+    source::{self, Fetched, ResumeSource, Section},
+};
+
+/// What the API serves, each made of one or more sections of the resume.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Resource {
+    Resume,
+    Employment,
+    Education,
+    About,
+    Contact,
+}
+
+impl Resource {
+    const ALL: [Resource; 5] = [
+        Resource::Resume,
+        Resource::Employment,
+        Resource::Education,
+        Resource::About,
+        Resource::Contact,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Resource::Resume => "resume",
+            Resource::Employment => "employment",
+            Resource::Education => "education",
+            Resource::About => "about",
+            Resource::Contact => "contact",
+        }
+    }
+
+    fn sections(&self) -> &'static [Section] {
+        match self {
+            Resource::Resume => &Section::ALL,
+            Resource::Employment => &[Section::Employment],
+            Resource::Education => &[Section::Education],
+            Resource::About => &[Section::AboutMe],
+            Resource::Contact => &[Section::Contact],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Representation {
+    Json,
+    Yaml,
+    Markdown,
+}
+
+impl Representation {
+    fn content_type(&self) -> &'static str {
+        match self {
+            Representation::Json => "application/json",
+            Representation::Yaml => "application/yaml",
+            Representation::Markdown => "text/markdown; charset=utf-8",
+        }
+    }
+
+    fn for_media_type(media_type: &str) -> Option<Self> {
+        match media_type {
+            "application/json" | "application/*" | "*/*" => Some(Representation::Json),
+            "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => {
+                Some(Representation::Yaml)
+            }
+            "text/markdown" | "text/*" => Some(Representation::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Picks what to send for an `Accept` header: the acceptable type with
+    /// the highest quality, the first listed on a tie, and JSON if the
+    /// client did not say.
+    fn negotiate(accept: Option<&str>) -> Option<Self> {
+        let accept = match accept {
+            Some(accept) if !accept.trim().is_empty() => accept,
+            _ => return Some(Representation::Json),
+        };
+
+        let mut best: Option<(Self, f32)> = None;
+        for range in accept.split(',') {
+            let mut parameters = range.split(';');
+            let media_type = parameters
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase();
+            let quality = parameters
+                .filter_map(|parameter| parameter.trim().strip_prefix("q="))
+                .find_map(|quality| quality.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality <= 0.0 {
+                continue;
+            }
+            if let Some(representation) = Self::for_media_type(&media_type) {
+                if best.is_none_or(|(_, best)| quality > best) {
+                    best = Some((representation, quality));
+                }
+            }
+        }
+        best.map(|(representation, _)| representation)
+    }
+}
+
+/// How long a revalidated section is trusted before the source is asked
+/// again, so a burst of requests costs one round trip per section.
+const REVALIDATE_AFTER: Duration = Duration::from_secs(5);
+
+/// A section as last fetched, and when its content last changed.
+struct Document {
+    bytes: Vec<u8>,
+    etag: Option<String>,
+    /// The source's own modification time where it has one, such as S3's
+    /// `Last-Modified`. Otherwise when this process first saw these bytes,
+    /// which starts again whenever the server restarts.
+    modified: SystemTime,
+    /// When the source was last asked whether this changed.
+    checked: Instant,
+}
+
+struct Api {
+    source: Arc<dyn ResumeSource>,
+    documents: Mutex<HashMap<&'static str, Document>>,
+}
+
+impl Api {
+    fn new(source: Arc<dyn ResumeSource>) -> Self {
+        Self {
+            source,
+            documents: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Revalidates `section` against the source, unless that was done in
+    /// the last `REVALIDATE_AFTER`. If the source fails, the last good copy
+    /// keeps being served rather than the API failing too.
+    async fn refresh(&self, section: Section) -> Result<(), LoadError> {
+        let key = section.key();
+        let etag = match self.documents.lock().unwrap().get(key) {
+            Some(document) if document.checked.elapsed() < REVALIDATE_AFTER => return Ok(()),
+            Some(document) => document.etag.clone(),
+            None => None,
+        };
+
+        let fetched = self.source.fetch_if_changed(key, etag.as_deref()).await;
+        let mut documents = self.documents.lock().unwrap();
+        let (bytes, etag, modified) = match fetched {
+            Ok(Fetched::Modified {
+                bytes,
+                etag,
+                modified,
+            }) => (bytes, etag, modified),
+            Err(err) if err.is_not_found() && section.is_optional() => (b"{}".to_vec(), None, None),
+            Ok(Fetched::NotModified) | Err(_) if documents.contains_key(key) => {
+                documents.get_mut(key).unwrap().checked = Instant::now();
+                return Ok(());
+            }
+            Ok(Fetched::NotModified) => {
+                return Err(LoadError::Request(format!(
+                    "{} was not modified, but was never fetched",
+                    key
+                )))
+            }
+            Err(err) => return Err(err),
+        };
+
+        match documents.get_mut(key) {
+            Some(document) if document.bytes == bytes => {
+                document.etag = etag;
+                document.modified = modified.unwrap_or(document.modified);
+                document.checked = Instant::now();
+            }
+            _ => {
+                documents.insert(
+                    key,
+                    Document {
+                        bytes,
+                        etag,
+                        modified: modified.unwrap_or_else(SystemTime::now),
+                        checked: Instant::now(),
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds a resume out of the sections `resource` needs, along with when
+    /// the most recent of them changed.
+    async fn load(&self, resource: Resource) -> Result<(Resume, SystemTime), LoadError> {
+        for &section in resource.sections() {
+            self.refresh(section).await?;
+        }
+
+        let documents = self.documents.lock().unwrap();
+        let mut resume = Resume::default();
+        let mut modified = UNIX_EPOCH;
+        for &section in resource.sections() {
+            let document = &documents[section.key()];
+            modified = modified.max(document.modified);
+            let bytes = document.bytes.as_slice();
+            match section {
+                Section::Employment => resume.employment = parse_json(section, bytes)?,
+                Section::Education => resume.education = parse_json(section, bytes)?,
+                Section::Contact => resume.contact = parse_json(section, bytes)?,
+                Section::AboutMe => resume.about = parse_json(section, bytes)?,
+                Section::Profile => resume.profile = parse_json(section, bytes)?,
+            }
+        }
+        Ok((resume, modified))
+    }
+}
+
+fn encode<T: Serialize>(
+    value: &T,
+    representation: Representation,
+) -> Result<String, Box<dyn Error>> {
+    match representation {
+        Representation::Yaml => Ok(serde_yaml::to_string(value)?),
+        _ => Ok(serde_json::to_string_pretty(value)? + "\n"),
+    }
+}
+
+fn body(
+    resume: &Resume,
+    resource: Resource,
+    representation: Representation,
+) -> Result<String, Box<dyn Error>> {
+    match (resource, representation) {
+        (Resource::Resume, Representation::Markdown) => Ok(markdown::render(resume)),
+        (_, Representation::Markdown) => Ok(markdown::render_sections(resume)),
+        (Resource::Resume, _) => encode(resume, representation),
+        (Resource::Employment, _) => encode(&resume.employment, representation),
+        (Resource::Education, _) => encode(&resume.education, representation),
+        (Resource::About, _) => encode(&resume.about, representation),
+        (Resource::Contact, _) => encode(&resume.contact, representation),
+    }
+}
+
+/// A strong validator for `body`, so each representation gets its own.
+fn etag(body: &str) -> String {
+    let digest = Sha256::digest(body.as_bytes());
+    let hex: String = digest[..16]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("\"{}\"", hex)
+}
+
+/// Whether the client's copy is out of date. `If-None-Match` wins over
+/// `If-Modified-Since` when both are sent, as RFC 9110 asks.
+fn is_modified(headers: &HeaderMap, etag: &str, modified: SystemTime) -> bool {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+    if let Some(tags) = header(header::IF_NONE_MATCH) {
+        return !tags
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == "*" || tag == etag);
+    }
+    match header(header::IF_MODIFIED_SINCE).and_then(|date| httpdate::parse_http_date(date).ok()) {
+        Some(since) => {
+            // HTTP dates stop at whole seconds.
+            let seconds = modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+            UNIX_EPOCH + Duration::from_secs(seconds.as_secs()) > since
+        }
+        None => true,
+    }
+}
+
+async fn respond(api: &Api, resource: Resource, headers: &HeaderMap) -> Response {
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok());
+    let Some(representation) = Representation::negotiate(accept) else {
+        return (
+            StatusCode::NOT_ACCEPTABLE,
+            "available as application/json, application/yaml or text/markdown\n",
+        )
+            .into_response();
+    };
+
+    let (resume, modified) = match api.load(resource).await {
+        Ok(loaded) => loaded,
+        Err(err) => return (StatusCode::BAD_GATEWAY, err.to_string()).into_response(),
+    };
+    let body = match body(&resume, resource, representation) {
+        Ok(body) => body,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+    };
+
+    let etag = etag(&body);
+    let validators = [
+        (header::ETAG, etag.clone()),
+        (header::LAST_MODIFIED, httpdate::fmt_http_date(modified)),
+        (header::VARY, String::from("Accept")),
+        (header::CACHE_CONTROL, String::from("no-cache")),
+    ];
+    if !is_modified(headers, &etag, modified) {
+        return (StatusCode::NOT_MODIFIED, validators).into_response();
+    }
+    (
+        validators,
+        [(header::CONTENT_TYPE, representation.content_type())],
+        body,
+    )
+        .into_response()
+}
+
+async fn resource(
+    State(api): State<Arc<Api>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    match Resource::ALL
+        .into_iter()
+        .find(|resource| resource.name() == name)
+    {
+        Some(resource) => respond(&api, resource, &headers).await,
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Serves the resume, and each section of it, as JSON, YAML or Markdown.
+/// Requests revalidate the sections they need against the source, at most
+/// once every `REVALIDATE_AFTER`.
+pub async fn serve(settings: &Settings, addr: SocketAddr) -> Result<(), Box<dyn Error>> {
+    let api = Arc::new(Api::new(source::from_settings(settings).await));
+    let app = Router::new()
+        .route("/{resource}", get(resource))
+        .with_state(api);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!(
+        "serving the resume API on http://{}",
+        listener.local_addr()?
+    );
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+    use crate::source::SampleSource;
+
+    #[test]
+    fn test_negotiate() {
+        use Representation::*;
+
+        assert_eq!(Representation::negotiate(None), Some(Json));
+        assert_eq!(Representation::negotiate(Some("*/*")), Some(Json));
+        assert_eq!(
+            Representation::negotiate(Some("text/html, text/markdown")),
+            Some(Markdown)
+        );
+        assert_eq!(
+            Representation::negotiate(Some("application/json;q=0.5, application/yaml")),
+            Some(Yaml)
+        );
+        assert_eq!(
+            Representation::negotiate(Some("text/markdown;q=0, text/yaml;q=0.1")),
+            Some(Yaml)
+        );
+        assert_eq!(Representation::negotiate(Some("image/png")), None);
+    }
+
+    #[tokio::test]
+    async fn test_conditional_requests() {
+        let api = Api::new(Arc::new(SampleSource));
+        let response = respond(&api, Resource::Employment, &HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        let etag = response.headers()[header::ETAG].clone();
+        let last_modified = response.headers()[header::LAST_MODIFIED].clone();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag.clone());
+        let response = respond(&api, Resource::Employment, &headers).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        // The Markdown is a different representation, so a different tag.
+        headers.insert(header::ACCEPT, HeaderValue::from_static("text/markdown"));
+        let response = respond(&api, Resource::Employment, &headers).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[header::ETAG], etag);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MODIFIED_SINCE, last_modified);
+        let response = respond(&api, Resource::Employment, &headers).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    }
+
+    /// Counts requests, and dates every document to the same moment.
+    #[derive(Default)]
+    struct CountingSource {
+        requests: Mutex<usize>,
+    }
+
+    #[async_trait::async_trait]
+    impl ResumeSource for CountingSource {
+        async fn fetch(&self, key: &str) -> Result<Vec<u8>, LoadError> {
+            SampleSource.fetch(key).await
+        }
+
+        async fn fetch_if_changed(
+            &self,
+            key: &str,
+            _etag: Option<&str>,
+        ) -> Result<Fetched, LoadError> {
+            *self.requests.lock().unwrap() += 1;
+            Ok(Fetched::Modified {
+                bytes: self.fetch(key).await?,
+                etag: None,
+                modified: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_revalidation_is_reused_and_dated_by_the_source() {
+        let source = Arc::new(CountingSource::default());
+        let api = Api::new(source.clone());
+
+        for _ in 0..3 {
+            let response = respond(&api, Resource::Resume, &HeaderMap::new()).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.headers()[header::LAST_MODIFIED],
+                "Tue, 14 Nov 2023 22:13:20 GMT"
+            );
+        }
+        assert_eq!(*source.requests.lock().unwrap(), Section::ALL.len());
+
+        api.documents
+            .lock()
+            .unwrap()
+            .values_mut()
+            .for_each(|document| document.checked -= REVALIDATE_AFTER);
+        respond(&api, Resource::Employment, &HeaderMap::new()).await;
+        assert_eq!(*source.requests.lock().unwrap(), Section::ALL.len() + 1);
+    }
+}
@@ -0,0 +1,4 @@
+/// Resume documents compiled into the binary for `--source bundled`, by key,
+/// e.g. `(EMPLOYMENT_KEY, include_str!("data/employment.json"))`. None ship
+/// with the repository: list the files here to build a self-contained binary.
+pub const DOCUMENTS: &[(&str, &str)] = &[];
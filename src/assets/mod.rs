@@ -1 +1,2 @@
 pub mod banner;
+pub mod data;
@@ -0,0 +1,116 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::settings::Settings;
+
+pub struct CachedObject {
+    pub bytes: Vec<u8>,
+    pub etag: Option<String>,
+}
+
+/// On-disk copies of remote resume documents, stored as
+/// `<dir>/<namespace>/<key>` with the ETag alongside in `<key>.etag`.
+#[derive(Clone)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// `$XDG_CACHE_HOME/resume-rs`, falling back to `~/.cache/resume-rs`.
+    pub fn in_default_location() -> Option<Self> {
+        let base = match env::var_os("XDG_CACHE_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".cache"),
+        };
+        Some(Self::new(base.join("resume-rs")))
+    }
+
+    pub fn for_settings(settings: &Settings) -> Option<Self> {
+        match settings.use_cache {
+            true => Self::in_default_location(),
+            false => None,
+        }
+    }
+
+    fn path(&self, namespace: &str, key: &str) -> PathBuf {
+        self.dir.join(namespace).join(key)
+    }
+
+    fn etag_path(path: &Path) -> PathBuf {
+        let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(".etag");
+        path.with_file_name(file_name)
+    }
+
+    pub fn read(&self, namespace: &str, key: &str) -> Option<CachedObject> {
+        let path = self.path(namespace, key);
+        let bytes = fs::read(&path).ok()?;
+        let etag = fs::read_to_string(Self::etag_path(&path)).ok();
+        Some(CachedObject { bytes, etag })
+    }
+
+    pub fn write(
+        &self,
+        namespace: &str,
+        key: &str,
+        bytes: &[u8],
+        etag: Option<&str>,
+    ) -> io::Result<()> {
+        let path = self.path(namespace, key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut tmp = path.clone().into_os_string();
+        tmp.push(".tmp");
+        fs::write(&tmp, bytes)?;
+        fs::rename(&tmp, &path)?;
+
+        let etag_path = Self::etag_path(&path);
+        match etag {
+            Some(etag) => fs::write(etag_path, etag),
+            None => match fs::remove_file(etag_path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_then_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        cache
+            .write("bucket", "employment.json", b"[]", Some("\"abc\""))
+            .unwrap();
+
+        let cached = cache.read("bucket", "employment.json").unwrap();
+        assert_eq!(cached.bytes, b"[]");
+        assert_eq!(cached.etag.as_deref(), Some("\"abc\""));
+
+        cache
+            .write("bucket", "employment.json", b"[1]", None)
+            .unwrap();
+        let cached = cache.read("bucket", "employment.json").unwrap();
+        assert_eq!(cached.bytes, b"[1]");
+        assert_eq!(cached.etag, None);
+    }
+
+    #[test]
+    fn test_read_missing_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        assert!(cache.read("bucket", "nothing.json").is_none());
+    }
+}
@@ -0,0 +1,194 @@
+use std::{net::SocketAddr, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+
+use crate::{
+    config::{Config, S3Config},
+    export::{Format, Options},
+    session::ssh,
+    source::Section,
+};
+
+#[derive(Debug, Parser)]
+#[command(name = "app", about = "A very rusty resume")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Read configuration from this file instead of
+    /// $XDG_CONFIG_HOME/resume-rs/config.toml
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Where to load resume data from: s3, bundled, an http(s) URL, a directory
+    /// or a single resume document (ours or JSON Resume)
+    #[arg(long, global = true, value_name = "SOURCE")]
+    pub source: Option<String>,
+
+    /// Read a single-file --source as native or json-resume, rather than by
+    /// its $schema
+    #[arg(long, global = true, value_name = "FORMAT")]
+    pub document_format: Option<String>,
+
+    /// Load resume data from JSON files in this directory
+    #[arg(long, global = true, value_name = "DIR", conflicts_with_all = ["source", "bucket"])]
+    pub data_dir: Option<PathBuf>,
+
+    /// Load resume data from this S3 bucket
+    #[arg(long, global = true, value_name = "NAME", conflicts_with = "source")]
+    pub bucket: Option<String>,
+
+    /// Reload the resume when files in the data directory change
+    #[arg(long, global = true)]
+    pub watch: bool,
+
+    /// How long to wait for input between redraws, in milliseconds
+    #[arg(long, global = true, value_name = "MS")]
+    pub poll_interval_ms: Option<u64>,
+
+    /// Colour theme: green, amber, blue or mono
+    #[arg(long, global = true)]
+    pub theme: Option<String>,
+
+    /// Draw without colours, the same as --theme mono
+    #[arg(long, global = true, conflicts_with = "theme")]
+    pub no_color: bool,
+
+    /// Tab to open on start: about, contact or employment
+    #[arg(long, global = true)]
+    pub tab: Option<String>,
+
+    /// Print the effective configuration as TOML and exit
+    #[arg(long, global = true)]
+    pub print_config: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Browse the resume in the terminal (the default)
+    Tui,
+    /// Write the resume out in another format
+    Export {
+        #[arg(long, value_enum, default_value = "json")]
+        format: Format,
+        /// Write to this file instead of standard output
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        options: Options,
+    },
+    /// Check that every section of the resume loads from the source
+    Validate,
+    /// Serve the resume documents over HTTP for other instances to load, or
+    /// the resume itself to remote viewers
+    Serve {
+        /// Address to listen on [default: 127.0.0.1:8080, 127.0.0.1:2222 with
+        /// --ssh or 127.0.0.1:2323 with --telnet]
+        #[arg(long)]
+        addr: Option<SocketAddr>,
+        /// Serve the interactive resume over SSH instead
+        #[arg(long)]
+        ssh: bool,
+        /// Serve the interactive resume to browsers instead, in a terminal
+        /// emulator on a web page
+        #[arg(long, conflicts_with = "ssh")]
+        web: bool,
+        /// Serve the resume and each of its sections as JSON, YAML or
+        /// Markdown instead, at /resume, /employment, /education, /about
+        /// and /contact
+        #[arg(long, conflicts_with_all = ["ssh", "web"])]
+        api: bool,
+        /// Serve the interactive resume over telnet instead
+        #[arg(long, conflicts_with_all = ["ssh", "web", "api"])]
+        telnet: bool,
+        /// Most viewers connected at once with --ssh, --telnet or --web; more
+        /// are turned away
+        #[arg(long, default_value_t = 16, value_name = "COUNT")]
+        max_connections: usize,
+        #[command(flatten)]
+        ssh_options: ssh::Options,
+    },
+    /// Download the resume documents into a directory for use with --data-dir
+    Fetch {
+        #[arg(long, short, default_value = "data")]
+        output: PathBuf,
+    },
+    /// Print the resume, or one section of it, as plain text
+    Print {
+        /// about, contact, employment or education
+        section: Option<Section>,
+    },
+}
+
+impl Cli {
+    pub fn config_layer(&self) -> Config {
+        let source = match (&self.data_dir, &self.bucket) {
+            (Some(dir), _) => Some(dir.display().to_string()),
+            (None, Some(_)) => Some(String::from("s3")),
+            (None, None) => self.source.clone(),
+        };
+        let theme = match self.no_color {
+            true => Some(String::from("mono")),
+            false => self.theme.clone(),
+        };
+
+        Config {
+            poll_interval_ms: self.poll_interval_ms,
+            source,
+            document_format: self.document_format.clone(),
+            watch: self.watch.then_some(true),
+            theme,
+            initial_tab: self.tab.clone(),
+            s3: S3Config {
+                bucket: self.bucket.clone(),
+                ..S3Config::default()
+            },
+            ..Config::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_subcommand_runs_the_tui() {
+        let cli = Cli::try_parse_from(["app", "--tab", "contact"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.config_layer().initial_tab.as_deref(), Some("contact"));
+    }
+
+    #[test]
+    fn test_global_flags_after_subcommand() {
+        let cli =
+            Cli::try_parse_from(["app", "print", "education", "--data-dir", "./data"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Print {
+                section: Some(Section::Education)
+            })
+        ));
+        assert_eq!(cli.config_layer().source.as_deref(), Some("./data"));
+    }
+
+    #[test]
+    fn test_bucket_selects_s3() {
+        let cli = Cli::try_parse_from(["app", "validate", "--bucket", "staging"]).unwrap();
+        let layer = cli.config_layer();
+        assert_eq!(layer.source.as_deref(), Some("s3"));
+        assert_eq!(layer.s3.bucket.as_deref(), Some("staging"));
+    }
+
+    #[test]
+    fn test_no_color_selects_mono_theme() {
+        let cli = Cli::try_parse_from(["app", "--no-color"]).unwrap();
+        assert_eq!(cli.config_layer().theme.as_deref(), Some("mono"));
+    }
+
+    #[test]
+    fn test_conflicting_sources_are_rejected() {
+        assert!(Cli::try_parse_from(["app", "--data-dir", "data", "--bucket", "b"]).is_err());
+        assert!(Cli::try_parse_from(["app", "--source", "bundled", "--bucket", "b"]).is_err());
+    }
+}
@@ -0,0 +1,37 @@
+use std::{
+    error::Error,
+    fs,
+    io::{self, IsTerminal, Write},
+    path::Path,
+};
+
+use super::{ensure_loaded, load_state};
+use crate::{
+    export::{self, Format, Options},
+    settings::Settings,
+};
+
+pub async fn export(
+    settings: &Settings,
+    format: Format,
+    options: &Options,
+    output: Option<&Path>,
+) -> Result<(), Box<dyn Error>> {
+    if output.is_none() && !format.is_text() && io::stdout().is_terminal() {
+        return Err("refusing to write a PDF to the terminal, use -o or redirect".into());
+    }
+
+    let state = load_state(settings, settings.use_cache).await;
+    ensure_loaded(&state)?;
+
+    let options = Options {
+        template_dir: settings.latex_template_dir.clone(),
+        ..options.clone()
+    };
+    let rendered = export::render(&state, format, &options)?;
+    match output {
+        Some(path) => fs::write(path, rendered)?,
+        None => io::stdout().write_all(&rendered)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use std::{error::Error, fs, path::Path};
+
+use crate::{
+    error::LoadError,
+    settings::Settings,
+    source::{self, Section},
+};
+
+/// Downloads every document from the configured source into `output`, so it
+/// can be used offline with `--data-dir`.
+pub async fn fetch(settings: &Settings, output: &Path) -> Result<(), Box<dyn Error>> {
+    let source = source::from_settings(settings).await;
+    fs::create_dir_all(output)?;
+
+    for section in Section::ALL {
+        let bytes = match source.fetch(section.key()).await {
+            Ok(bytes) => bytes,
+            Err(err) if err.is_not_found() && section.is_optional() => continue,
+            Err(err) => return Err(err.into()),
+        };
+        serde_json::from_slice::<serde_json::Value>(&bytes)
+            .map_err(|err| LoadError::malformed_json(section.key(), err))?;
+
+        let path = output.join(section.key());
+        fs::write(&path, bytes)?;
+        println!("{} -> {}", section.key(), path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use std::error::Error;
+
+use crate::{
+    cache::Cache,
+    loader::Loader,
+    settings::Settings,
+    source::{self, Section},
+    state::State,
+};
+
+mod export;
+mod fetch;
+mod print;
+mod serve;
+mod validate;
+
+pub use export::export;
+pub use fetch::fetch;
+pub use print::print;
+pub use serve::{serve, serve_api, serve_ssh, serve_telnet, serve_web};
+pub use validate::validate;
+
+/// Loads every section into a fresh `State` the same way the TUI does, but
+/// waits for all of it instead of drawing as it arrives.
+async fn load_state(settings: &Settings, use_cache: bool) -> State {
+    let source = source::from_settings(settings).await;
+    let cache = match use_cache {
+        true => Cache::for_settings(settings),
+        false => None,
+    };
+
+    let mut state = State::default();
+    state.theme = settings.theme;
+    let loader = Loader::new(source).with_cache(cache);
+    state.start_loading(&Section::ALL);
+    loader.load(&state.take_load_requests());
+    for event in loader.finish().await {
+        state.apply_load_event(event);
+    }
+    state
+}
+
+fn ensure_loaded(state: &State) -> Result<(), Box<dyn Error>> {
+    match state.load_errors.first() {
+        Some((section, err)) => Err(format!("could not load {}: {}", section.name(), err).into()),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,76 @@
+use std::error::Error;
+
+use super::{ensure_loaded, load_state};
+use crate::{settings::Settings, source::Section, state::State};
+
+fn print_section(state: &State, section: Section) {
+    match section {
+        Section::Employment => {
+            println!("Employment");
+            for job in &state.resume.employment {
+                println!(
+                    "  {} at {}, {} - {}",
+                    job.role, job.employer, job.start_date, job.end_date
+                );
+            }
+        }
+        Section::AboutMe => {
+            println!("About me");
+            println!("  {}", state.resume.about.about_me);
+        }
+        Section::Contact => {
+            println!("Contact");
+            for field in state.resume.contact.fields() {
+                println!("  {}: {}", field.label, field.value);
+            }
+        }
+        Section::Profile => {
+            let profile = &state.resume.profile;
+            println!("Profile");
+            for (label, value) in [("Name", &profile.name), ("Headline", &profile.label)] {
+                if let Some(value) = value {
+                    println!("  {}: {}", label, value);
+                }
+            }
+            for skill in &profile.skills {
+                match skill.keywords.is_empty() {
+                    true => println!("  Skill: {}", skill.name),
+                    false => println!("  Skill: {} ({})", skill.name, skill.keywords.join(", ")),
+                }
+            }
+            for project in &profile.projects {
+                println!("  Project: {}", project.name);
+            }
+            for award in &profile.awards {
+                println!("  Award: {}", award.title);
+            }
+        }
+        Section::Education => {
+            println!("Education");
+            for course in &state.resume.education {
+                println!(
+                    "  {}, {}, {} - {}",
+                    course.qualification, course.educator, course.start_date, course.end_date
+                );
+            }
+        }
+    }
+}
+
+pub async fn print(settings: &Settings, section: Option<Section>) -> Result<(), Box<dyn Error>> {
+    let state = load_state(settings, settings.use_cache).await;
+    ensure_loaded(&state)?;
+
+    match section {
+        Some(section) => print_section(&state, section),
+        None => {
+            for (i, section) in Section::ALL.into_iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                print_section(&state, section);
+            }
+        }
+    }
+    Ok(())
+}
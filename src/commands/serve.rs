@@ -0,0 +1,106 @@
+use std::{
+    error::Error,
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+
+use crate::{
+    api,
+    session::{ssh, telnet, web},
+    settings::Settings,
+    source::{self, ResumeSource, Section},
+};
+
+async fn document(
+    State(source): State<Arc<dyn ResumeSource>>,
+    Path(key): Path<String>,
+) -> Response {
+    // Only the resume documents themselves are served, never arbitrary keys.
+    if !Section::ALL.iter().any(|section| section.key() == key) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    match source.fetch(&key).await {
+        Ok(bytes) => ([(header::CONTENT_TYPE, "application/json")], bytes).into_response(),
+        Err(err) if err.is_not_found() => StatusCode::NOT_FOUND.into_response(),
+        Err(err) => (StatusCode::BAD_GATEWAY, err.to_string()).into_response(),
+    }
+}
+
+fn local(port: u16) -> SocketAddr {
+    SocketAddr::from((Ipv4Addr::LOCALHOST, port))
+}
+
+/// Serves the raw resume documents from the configured source, so another
+/// instance can use this one as its `--source http://...`.
+pub async fn serve(settings: &Settings, addr: Option<SocketAddr>) -> Result<(), Box<dyn Error>> {
+    let addr = addr.unwrap_or_else(|| local(8080));
+    let source = source::from_settings(settings).await;
+    let app = Router::new()
+        .route("/{key}", get(document))
+        .with_state(source);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!(
+        "serving resume documents on http://{}",
+        listener.local_addr()?
+    );
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// Serves the TUI itself over SSH, a fresh session for each viewer.
+pub async fn serve_ssh(
+    settings: Settings,
+    addr: Option<SocketAddr>,
+    max_connections: usize,
+    options: &ssh::Options,
+) -> Result<(), Box<dyn Error>> {
+    let addr = addr.unwrap_or_else(|| local(2222));
+    ssh::serve(settings, addr, max_connections, options).await
+}
+
+/// Serves the TUI over telnet, a fresh session for each viewer.
+pub async fn serve_telnet(
+    settings: Settings,
+    addr: Option<SocketAddr>,
+    max_connections: usize,
+) -> Result<(), Box<dyn Error>> {
+    telnet::serve(
+        settings,
+        addr.unwrap_or_else(|| local(2323)),
+        max_connections,
+    )
+    .await
+}
+
+/// Serves the TUI to browsers, a fresh session for each tab.
+pub async fn serve_web(
+    settings: Settings,
+    addr: Option<SocketAddr>,
+    max_connections: usize,
+) -> Result<(), Box<dyn Error>> {
+    web::serve(
+        settings,
+        addr.unwrap_or_else(|| local(8080)),
+        max_connections,
+    )
+    .await
+}
+
+/// Serves the resume as an API for other programs, rather than the raw
+/// documents.
+pub async fn serve_api(
+    settings: &Settings,
+    addr: Option<SocketAddr>,
+) -> Result<(), Box<dyn Error>> {
+    api::serve(settings, addr.unwrap_or_else(|| local(8080))).await
+}
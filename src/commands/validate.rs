@@ -0,0 +1,27 @@
+use std::error::Error;
+
+use super::load_state;
+use crate::{settings::Settings, source::Section};
+
+/// Loads every section straight from the source, bypassing the cache, and
+/// reports which ones failed.
+pub async fn validate(settings: &Settings) -> Result<(), Box<dyn Error>> {
+    let state = load_state(settings, false).await;
+
+    for section in Section::ALL {
+        match state.load_error(section) {
+            Some(err) => println!("{}: {}", section.key(), err),
+            None => println!("{}: ok", section.key()),
+        }
+    }
+
+    match state.load_errors.len() {
+        0 => Ok(()),
+        failed => Err(format!(
+            "{} of {} sections failed to load",
+            failed,
+            Section::ALL.len()
+        )
+        .into()),
+    }
+}
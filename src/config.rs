@@ -0,0 +1,350 @@
+use std::{collections::BTreeMap, env, fs, io, path::Path, path::PathBuf};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::settings::Settings;
+
+/// One layer of configuration: the config file, the environment or the
+/// command line. Anything left unset falls through to the layer below.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_interval_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watch: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_tab: Option<String>,
+    pub s3: S3Config,
+    pub latex: LatexConfig,
+    #[serde(
+        deserialize_with = "deserialize_keybindings",
+        serialize_with = "serialize_keybindings"
+    )]
+    pub keybindings: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct S3Config {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force_path_style: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LatexConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_dir: Option<String>,
+}
+
+/// A key binding's value: one key, or a list of keys for the same event.
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl From<Keys> for Vec<String> {
+    fn from(keys: Keys) -> Self {
+        match keys {
+            Keys::One(key) => vec![key],
+            Keys::Many(keys) => keys,
+        }
+    }
+}
+
+/// `[keybindings]` maps events to a key or a list of keys, with a table per
+/// context for keys that only apply there. Those are flattened to
+/// `context.event` names.
+fn deserialize_keybindings<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, Vec<String>>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Keys(Keys),
+        Context(BTreeMap<String, Keys>),
+    }
+
+    let mut keybindings = BTreeMap::new();
+    for (name, entry) in BTreeMap::<String, Entry>::deserialize(deserializer)? {
+        match entry {
+            Entry::Keys(keys) => {
+                keybindings.insert(name, keys.into());
+            }
+            Entry::Context(table) => {
+                for (event, keys) in table {
+                    keybindings.insert(format!("{}.{}", name, event), keys.into());
+                }
+            }
+        }
+    }
+    Ok(keybindings)
+}
+
+/// Writes single keys as plain strings, the way they are usually configured.
+fn serialize_keybindings<S: Serializer>(
+    keybindings: &BTreeMap<String, Vec<String>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(keybindings.iter().map(|(name, keys)| {
+        let keys = match &keys[..] {
+            [key] => Keys::One(key.clone()),
+            keys => Keys::Many(keys.to_vec()),
+        };
+        (name, keys)
+    }))
+}
+
+fn parse_bool(name: &str, value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(format!("{} must be true or false, got {:?}", name, value)),
+    }
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/resume-rs/config.toml`, falling back to
+    /// `~/.config/resume-rs/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(base.join("resume-rs").join("config.toml"))
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, String> {
+        toml::from_str(contents).map_err(|err| err.to_string())
+    }
+
+    /// Reads the config file at `path`. A missing file is only an error if
+    /// it was asked for explicitly rather than found by default.
+    pub fn from_file(path: &Path, required: bool) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                Self::from_toml(&contents).map_err(|err| format!("{}: {}", path.display(), err))
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound && !required => Ok(Self::default()),
+            Err(err) => Err(format!("{}: {}", path.display(), err)),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let parse_u64 = |name: &str| -> Result<Option<u64>, String> {
+            var(name)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| format!("{} must be a number, got {:?}", name, value))
+                })
+                .transpose()
+        };
+        let parse_flag = |name: &str| -> Result<Option<bool>, String> {
+            var(name).map(|value| parse_bool(name, &value)).transpose()
+        };
+
+        Ok(Self {
+            poll_interval_ms: parse_u64("RESUME_POLL_INTERVAL_MS")?,
+            source: var("RESUME_SOURCE"),
+            document_format: var("RESUME_DOCUMENT_FORMAT"),
+            cache: parse_flag("RESUME_CACHE")?,
+            watch: parse_flag("RESUME_WATCH")?,
+            theme: var("RESUME_THEME"),
+            initial_tab: var("RESUME_TAB"),
+            s3: S3Config {
+                bucket: var("RESUME_AWS_BUCKET"),
+                region: var("RESUME_AWS_REGION"),
+                endpoint_url: var("RESUME_AWS_ENDPOINT_URL"),
+                force_path_style: parse_flag("RESUME_AWS_FORCE_PATH_STYLE")?,
+                key_prefix: var("RESUME_AWS_KEY_PREFIX"),
+                profile: var("RESUME_AWS_PROFILE"),
+            },
+            latex: LatexConfig {
+                template_dir: var("RESUME_LATEX_TEMPLATE_DIR"),
+            },
+            keybindings: var("RESUME_KEY_PRESET")
+                .map(|preset| (String::from("preset"), vec![preset]))
+                .into_iter()
+                .collect(),
+        })
+    }
+
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string_pretty(self).map_err(|err| err.to_string())
+    }
+}
+
+impl From<&Settings> for Config {
+    fn from(settings: &Settings) -> Self {
+        Self {
+            poll_interval_ms: Some(settings.poll_duration_ms),
+            source: Some(settings.data_source.to_string()),
+            document_format: settings.document_format.map(|format| format.to_string()),
+            cache: Some(settings.use_cache),
+            watch: Some(settings.watch),
+            theme: Some(settings.theme.to_string()),
+            initial_tab: Some(settings.initial_tab.name().to_string()),
+            s3: S3Config {
+                bucket: Some(settings.aws_bucket.clone()),
+                region: Some(settings.aws_region.clone()),
+                endpoint_url: settings.aws_endpoint_url.clone(),
+                force_path_style: Some(settings.aws_force_path_style),
+                key_prefix: Some(settings.aws_key_prefix.clone()),
+                profile: settings.aws_profile.clone(),
+            },
+            latex: LatexConfig {
+                template_dir: settings
+                    .latex_template_dir
+                    .as_ref()
+                    .map(|dir| dir.display().to_string()),
+            },
+            keybindings: settings.keybindings.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_toml() {
+        let config = Config::from_toml(
+            r#"
+            poll_interval_ms = 50
+            source = "bundled"
+            initial_tab = "employment"
+
+            [s3]
+            bucket = "staging-resume"
+            force_path_style = true
+
+            [latex]
+            template_dir = "~/resume/templates"
+
+            [keybindings]
+            preset = "vim"
+            next_employer = "n"
+            quit = ["q", "x"]
+
+            [keybindings.education]
+            next_education = ["J", "Down"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.poll_interval_ms, Some(50));
+        assert_eq!(config.source.as_deref(), Some("bundled"));
+        assert_eq!(config.initial_tab.as_deref(), Some("employment"));
+        assert_eq!(config.s3.bucket.as_deref(), Some("staging-resume"));
+        assert_eq!(config.s3.force_path_style, Some(true));
+        assert_eq!(
+            config.latex.template_dir.as_deref(),
+            Some("~/resume/templates")
+        );
+        assert_eq!(config.keybindings["preset"], ["vim"]);
+        assert_eq!(config.keybindings["next_employer"], ["n"]);
+        assert_eq!(config.keybindings["quit"], ["q", "x"]);
+        assert_eq!(
+            config.keybindings["education.next_education"],
+            ["J", "Down"]
+        );
+        assert_eq!(config.theme, None);
+    }
+
+    #[test]
+    fn test_from_toml_rejects_unknown_keys() {
+        assert!(Config::from_toml("poll_interval = 50").is_err());
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let path = Path::new("/definitely/not/here/config.toml");
+        assert!(Config::from_file(path, false).is_ok());
+        assert!(Config::from_file(path, true).is_err());
+    }
+
+    #[test]
+    fn test_from_vars() {
+        let vars = BTreeMap::from([
+            ("RESUME_POLL_INTERVAL_MS", "250"),
+            ("RESUME_THEME", "blue"),
+            ("RESUME_AWS_FORCE_PATH_STYLE", "yes"),
+            ("RESUME_AWS_KEY_PREFIX", "staging"),
+            ("RESUME_KEY_PRESET", "emacs"),
+        ]);
+        let config = Config::from_vars(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+
+        assert_eq!(config.poll_interval_ms, Some(250));
+        assert_eq!(config.theme.as_deref(), Some("blue"));
+        assert_eq!(config.s3.force_path_style, Some(true));
+        assert_eq!(config.s3.key_prefix.as_deref(), Some("staging"));
+        assert_eq!(config.keybindings["preset"], ["emacs"]);
+        assert_eq!(config.source, None);
+    }
+
+    #[test]
+    fn test_from_vars_rejects_bad_values() {
+        assert!(Config::from_vars(|name| match name {
+            "RESUME_AWS_FORCE_PATH_STYLE" => Some(String::from("maybe")),
+            _ => None,
+        })
+        .is_err());
+        assert!(Config::from_vars(|name| match name {
+            "RESUME_POLL_INTERVAL_MS" => Some(String::from("soon")),
+            _ => None,
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_effective_config_round_trips() {
+        let mut settings = Settings::default();
+        settings.aws_key_prefix = String::from("prod");
+        settings
+            .keybindings
+            .insert(String::from("quit"), vec![String::from("x")]);
+        settings.keybindings.insert(
+            String::from("about.scroll_about_me_text_down"),
+            vec![String::from("J"), String::from("Down")],
+        );
+
+        let printed = Config::from(&settings).to_toml().unwrap();
+        let reparsed = Settings::from_layers(vec![Config::from_toml(&printed).unwrap()]).unwrap();
+
+        assert_eq!(reparsed.aws_key_prefix, "prod");
+        assert!(printed.contains("quit = \"x\""));
+        assert_eq!(reparsed.keybindings["quit"], ["x"]);
+        assert_eq!(
+            reparsed.keybindings["about.scroll_about_me_text_down"],
+            ["J", "Down"]
+        );
+        assert_eq!(reparsed.data_source, settings.data_source);
+    }
+}
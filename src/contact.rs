@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Link {
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ContactDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+    /// A GitHub username or profile URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub github: Option<String>,
+    /// A LinkedIn profile name or URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linkedin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Link>,
+}
+
+/// One row of the contact table.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ContactField {
+    pub label: String,
+    pub value: String,
+    pub url: Option<String>,
+}
+
+pub const GITHUB_URL: &str = "https://github.com/";
+pub const LINKEDIN_URL: &str = "https://www.linkedin.com/in/";
+
+/// Turns a bare username into a profile URL, leaving full URLs alone.
+pub fn profile_url(value: &str, base: &str) -> String {
+    match value.starts_with("http://") || value.starts_with("https://") {
+        true => value.to_string(),
+        false => format!("{}{}", base, value.trim_start_matches('@')),
+    }
+}
+
+impl ContactDetails {
+    pub fn fields(&self) -> Vec<ContactField> {
+        let mut fields = Vec::new();
+        let mut push = |label: &str, value: &Option<String>, url: fn(&str) -> Option<String>| {
+            if let Some(value) = value {
+                fields.push(ContactField {
+                    label: label.to_string(),
+                    value: value.clone(),
+                    url: url(value),
+                });
+            }
+        };
+
+        push("Email", &self.email, |v| Some(format!("mailto:{}", v)));
+        push("Phone", &self.phone, |v| {
+            Some(format!("tel:{}", v.replace(' ', "")))
+        });
+        push("Website", &self.website, |v| {
+            Some(profile_url(v, "https://"))
+        });
+        push("GitHub", &self.github, |v| Some(profile_url(v, GITHUB_URL)));
+        push("LinkedIn", &self.linkedin, |v| {
+            Some(profile_url(v, LINKEDIN_URL))
+        });
+        push("Location", &self.location, |_| None);
+
+        for link in &self.links {
+            fields.push(ContactField {
+                label: link.label.clone(),
+                value: link.url.clone(),
+                url: Some(link.url.clone()),
+            });
+        }
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fields_skip_missing_values() {
+        let contact: ContactDetails = serde_json::from_str(
+            r#"{
+                "email": "me@example.com",
+                "github": "octocat",
+                "location": "London",
+                "links": [{"label": "Blog", "url": "https://blog.example.com"}]
+            }"#,
+        )
+        .unwrap();
+
+        let fields = contact.fields();
+        let labels: Vec<&str> = fields.iter().map(|f| f.label.as_str()).collect();
+        assert_eq!(labels, vec!["Email", "GitHub", "Location", "Blog"]);
+        assert_eq!(fields[0].url.as_deref(), Some("mailto:me@example.com"));
+        assert_eq!(fields[1].url.as_deref(), Some("https://github.com/octocat"));
+        assert_eq!(fields[2].url, None);
+        assert_eq!(fields[3].url.as_deref(), Some("https://blog.example.com"));
+    }
+
+    #[test]
+    fn test_profile_urls_are_kept() {
+        let contact = ContactDetails {
+            linkedin: Some(String::from("https://www.linkedin.com/in/someone")),
+            website: Some(String::from("example.com")),
+            ..ContactDetails::default()
+        };
+
+        let fields = contact.fields();
+        assert_eq!(fields[0].url.as_deref(), Some("https://example.com"));
+        assert_eq!(
+            fields[1].url.as_deref(),
+            Some("https://www.linkedin.com/in/someone")
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::io::{self, Write};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::state::Effect;
+
+/// Copies `text` with an OSC 52 escape sequence, which asks the terminal
+/// itself to set the clipboard. Unlike a system clipboard library this also
+/// works when the resume is viewed over a remote connection.
+fn copy_to_clipboard(out: &mut impl Write, text: &str) -> io::Result<()> {
+    write!(out, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    out.flush()
+}
+
+/// Performs `effect` and describes the outcome for the status line.
+pub fn perform(effect: Effect, out: &mut impl Write) -> String {
+    match effect {
+        Effect::CopyToClipboard(text) => match copy_to_clipboard(out, &text) {
+            Ok(()) => format!("Copied {}", text),
+            Err(err) => format!("Could not copy: {}", err),
+        },
+        Effect::OpenUrl(url) => match open::that_detached(&url) {
+            Ok(()) => format!("Opened {}", url),
+            Err(err) => format!("Could not open {}: {}", url, err),
+        },
+    }
+}
+
+/// Performs `effect` for a viewer connected over the network. Their browser
+/// is on the other end of the connection, so links are copied for them to
+/// open rather than opened on this machine.
+pub fn perform_remote(effect: Effect, out: &mut impl Write) -> String {
+    match effect {
+        Effect::OpenUrl(url) => match copy_to_clipboard(out, &url) {
+            Ok(()) => format!("Copied {}, paste it into your browser", url),
+            Err(err) => format!("Could not copy: {}", err),
+        },
+        effect => perform(effect, out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_writes_osc_52() {
+        let mut out = Vec::new();
+        let status = perform(Effect::CopyToClipboard(String::from("hi")), &mut out);
+        assert_eq!(out, b"\x1b]52;c;aGk=\x07");
+        assert_eq!(status, "Copied hi");
+    }
+
+    #[test]
+    fn test_remote_open_copies_instead() {
+        let mut out = Vec::new();
+        let status = perform_remote(Effect::OpenUrl(String::from("hi")), &mut out);
+        assert_eq!(out, b"\x1b]52;c;aGk=\x07");
+        assert_eq!(status, "Copied hi, paste it into your browser");
+    }
+}
@@ -1,19 +1,39 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Clone)]
+/// The longer description of an employment or education entry, shown in the
+/// detail pane. Every field is optional so older documents still load.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct Details {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub achievements: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub technologies: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Employment {
     pub employer: String,
     pub role: String,
     pub start_date: String,
     pub end_date: String,
+    #[serde(flatten)]
+    pub details: Details,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Education {
     pub educator: String,
     pub qualification: String,
     pub start_date: String,
     pub end_date: String,
+    #[serde(flatten)]
+    pub details: Details,
 }
 
 impl Employment {
@@ -34,6 +54,7 @@ impl Employment {
             role: String::from("role"),
             start_date: String::from("start date"),
             end_date: String::from("end date"),
+            details: Details::default(),
         }
     }
 }
@@ -56,6 +77,7 @@ impl Education {
             qualification: String::from("qualification"),
             start_date: String::from("start date"),
             end_date: String::from("end date"),
+            details: Details::default(),
         }
     }
 }
@@ -65,3 +87,47 @@ pub enum EmploymentEducation {
     Employment,
     Education,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_employment_without_details() {
+        let employment: Employment = serde_json::from_str(
+            r#"{"employer": "e", "role": "r", "start_date": "s", "end_date": "e"}"#,
+        )
+        .unwrap();
+        assert!(employment.details.summary.is_none());
+        assert!(employment.details.achievements.is_empty());
+    }
+
+    #[test]
+    fn test_education_with_details() {
+        let education: Education = serde_json::from_str(
+            r#"{
+                "educator": "University",
+                "qualification": "MSc",
+                "start_date": "2015",
+                "end_date": "2016",
+                "summary": "Thesis on compilers",
+                "achievements": ["Distinction"],
+                "technologies": ["Rust", "LLVM"],
+                "location": "Edinburgh",
+                "link": "https://example.com"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            education.details.summary.as_deref(),
+            Some("Thesis on compilers")
+        );
+        assert_eq!(education.details.achievements, vec!["Distinction"]);
+        assert_eq!(education.details.technologies, vec!["Rust", "LLVM"]);
+        assert_eq!(education.details.location.as_deref(), Some("Edinburgh"));
+        assert_eq!(
+            education.details.link.as_deref(),
+            Some("https://example.com")
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::{error::Error, fmt, io};
+
+/// Why a section of the resume could not be loaded.
+#[derive(Debug)]
+pub enum LoadError {
+    MissingCredentials,
+    BucketNotFound(String),
+    NotFound(String),
+    NotBundled(String),
+    MalformedJson {
+        key: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    Io(io::Error),
+    Request(String),
+}
+
+impl LoadError {
+    pub fn malformed_json(key: &str, err: serde_json::Error) -> Self {
+        let location = format!(" at line {} column {}", err.line(), err.column());
+        let message = err.to_string();
+        Self::MalformedJson {
+            key: key.to_string(),
+            line: err.line(),
+            column: err.column(),
+            message: message.trim_end_matches(&location).to_string(),
+        }
+    }
+
+    /// Whether the document simply isn't there, whichever source said so.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Self::NotFound(_) | Self::NotBundled(_) => true,
+            Self::Io(err) => err.kind() == io::ErrorKind::NotFound,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingCredentials => write!(f, "no AWS credentials were found"),
+            Self::BucketNotFound(bucket) => write!(f, "bucket {} does not exist", bucket),
+            Self::NotFound(key) => write!(f, "{} does not exist", key),
+            Self::NotBundled(key) => write!(
+                f,
+                "{} is not bundled into this build (see src/assets/data.rs)",
+                key
+            ),
+            Self::MalformedJson {
+                key,
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "{} is not valid JSON (line {}, column {}): {}",
+                key, line, column, message
+            ),
+            Self::Io(err) => write!(f, "{}", err),
+            Self::Request(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_malformed_json_records_location() {
+        let err = serde_json::from_str::<Vec<String>>("[\n  \"a\",\n  oops\n]").unwrap_err();
+        match LoadError::malformed_json("employment.json", err) {
+            LoadError::MalformedJson {
+                key,
+                line,
+                column,
+                message,
+            } => {
+                assert_eq!(key, "employment.json");
+                assert_eq!(line, 3);
+                assert_eq!(column, 3);
+                assert_eq!(message, "expected value");
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+}
@@ -1,9 +1,71 @@
-#[derive(Clone)]
+/// Where an event's key binding applies: everywhere, or only on one tab
+/// (or, on the employment tab, one of its two lists).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Context {
+    Global,
+    AboutMe,
+    Contact,
+    Employment,
+    Education,
+    /// The help screen, which takes every key while it is open.
+    Help,
+}
+
+impl Context {
+    pub const ALL: [Context; 6] = [
+        Context::Global,
+        Context::AboutMe,
+        Context::Contact,
+        Context::Employment,
+        Context::Education,
+        Context::Help,
+    ];
+
+    /// The name used for this context's table under `[keybindings]`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Context::Global => "global",
+            Context::AboutMe => "about",
+            Context::Contact => "contact",
+            Context::Employment => "employment",
+            Context::Education => "education",
+            Context::Help => "help",
+        }
+    }
+
+    /// The heading for this context's keys on the help screen.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Context::Global => "Everywhere",
+            Context::AboutMe => "About Me",
+            Context::Contact => "Contact",
+            Context::Employment => "Employment",
+            Context::Education => "Education",
+            Context::Help => "Help",
+        }
+    }
+
+    /// Whether global bindings work here too. The help screen is modal, so
+    /// only its own do.
+    pub fn includes_global(&self) -> bool {
+        *self != Context::Help
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|context| context.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Event {
     Quit,
     SwitchToAboutMe,
     SwitchToContactInformation,
     SwitchToEmployment,
+    NextTab,
+    PreviousTab,
+    Retry,
+    ShowHelp,
 
     NextEmployer,
     PreviousEmployer,
@@ -11,43 +73,185 @@ pub enum Event {
     PreviousEducation,
     SelectEducation,
     SelectEmployment,
+    ScrollDetailsDown,
+    ScrollDetailsUp,
+
+    NextContactField,
+    PreviousContactField,
+    CopyContactField,
+    OpenContactLink,
 
     ScrollAboutMeTextDown,
     ScrollAboutMeTextUp,
+
+    FirstEntry,
+    LastEntry,
+    ScrollPageDown,
+    ScrollPageUp,
+
+    CloseHelp,
+    ScrollHelpDown,
+    ScrollHelpUp,
 }
 
 impl Event {
+    /// Every event, in the order the footer lists them.
+    pub const ALL: [Event; 29] = [
+        Self::Quit,
+        Self::SwitchToAboutMe,
+        Self::SwitchToContactInformation,
+        Self::SwitchToEmployment,
+        Self::NextTab,
+        Self::PreviousTab,
+        Self::Retry,
+        Self::ShowHelp,
+        Self::SelectEducation,
+        Self::NextEmployer,
+        Self::PreviousEmployer,
+        Self::SelectEmployment,
+        Self::NextEducation,
+        Self::PreviousEducation,
+        Self::ScrollDetailsDown,
+        Self::ScrollDetailsUp,
+        Self::NextContactField,
+        Self::PreviousContactField,
+        Self::CopyContactField,
+        Self::OpenContactLink,
+        Self::ScrollAboutMeTextDown,
+        Self::ScrollAboutMeTextUp,
+        Self::FirstEntry,
+        Self::LastEntry,
+        Self::ScrollPageDown,
+        Self::ScrollPageUp,
+        Self::CloseHelp,
+        Self::ScrollHelpDown,
+        Self::ScrollHelpUp,
+    ];
+
+    /// The name used for this event in the `[keybindings]` config table.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Quit => "quit",
+            Self::SwitchToAboutMe => "switch_to_about_me",
+            Self::SwitchToContactInformation => "switch_to_contact_information",
+            Self::SwitchToEmployment => "switch_to_employment",
+            Self::NextTab => "next_tab",
+            Self::PreviousTab => "previous_tab",
+            Self::Retry => "retry",
+            Self::ShowHelp => "show_help",
+            Self::NextEmployer => "next_employer",
+            Self::PreviousEmployer => "previous_employer",
+            Self::NextEducation => "next_education",
+            Self::PreviousEducation => "previous_education",
+            Self::SelectEducation => "select_education",
+            Self::SelectEmployment => "select_employment",
+            Self::ScrollDetailsDown => "scroll_details_down",
+            Self::ScrollDetailsUp => "scroll_details_up",
+            Self::NextContactField => "next_contact_field",
+            Self::PreviousContactField => "previous_contact_field",
+            Self::CopyContactField => "copy_contact_field",
+            Self::OpenContactLink => "open_contact_link",
+            Self::ScrollAboutMeTextDown => "scroll_about_me_text_down",
+            Self::ScrollAboutMeTextUp => "scroll_about_me_text_up",
+            Self::FirstEntry => "first_entry",
+            Self::LastEntry => "last_entry",
+            Self::ScrollPageDown => "scroll_page_down",
+            Self::ScrollPageUp => "scroll_page_up",
+            Self::CloseHelp => "close_help",
+            Self::ScrollHelpDown => "scroll_help_down",
+            Self::ScrollHelpUp => "scroll_help_up",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.name() == name)
+    }
+
+    pub fn contexts(&self) -> &'static [Context] {
+        match self {
+            Self::Quit
+            | Self::SwitchToAboutMe
+            | Self::SwitchToContactInformation
+            | Self::SwitchToEmployment
+            | Self::NextTab
+            | Self::PreviousTab
+            | Self::Retry
+            | Self::ShowHelp => &[Context::Global],
+            Self::NextEmployer | Self::PreviousEmployer | Self::SelectEducation => {
+                &[Context::Employment]
+            }
+            Self::NextEducation | Self::PreviousEducation | Self::SelectEmployment => {
+                &[Context::Education]
+            }
+            Self::ScrollDetailsDown | Self::ScrollDetailsUp => {
+                &[Context::Employment, Context::Education]
+            }
+            Self::NextContactField
+            | Self::PreviousContactField
+            | Self::CopyContactField
+            | Self::OpenContactLink => &[Context::Contact],
+            Self::ScrollAboutMeTextDown | Self::ScrollAboutMeTextUp => &[Context::AboutMe],
+            Self::FirstEntry | Self::LastEntry => &[
+                Context::Contact,
+                Context::Employment,
+                Context::Education,
+                Context::Help,
+            ],
+            Self::ScrollPageDown | Self::ScrollPageUp => &[
+                Context::AboutMe,
+                Context::Employment,
+                Context::Education,
+                Context::Help,
+            ],
+            Self::CloseHelp | Self::ScrollHelpDown | Self::ScrollHelpUp => &[Context::Help],
+        }
+    }
+
+    /// Whether the footer lists this event. Keys that work the same in most
+    /// programs are left to the help screen, to keep the footer short.
+    pub fn in_footer(&self) -> bool {
+        !matches!(
+            self,
+            Self::NextTab
+                | Self::PreviousTab
+                | Self::FirstEntry
+                | Self::LastEntry
+                | Self::ScrollPageDown
+                | Self::ScrollPageUp
+        )
+    }
+
     pub fn description(&self) -> &str {
         match self {
             Self::Quit => "quit",
             Self::SwitchToAboutMe => "about me",
             Self::SwitchToContactInformation => "contact",
             Self::SwitchToEmployment => "employment",
+            Self::NextTab => "next tab",
+            Self::PreviousTab => "previous tab",
+            Self::Retry => "retry",
+            Self::ShowHelp => "help",
             Self::NextEmployer => "next employer",
             Self::PreviousEmployer => "previous employer",
             Self::NextEducation => "next education",
             Self::PreviousEducation => "previous education",
             Self::SelectEducation => "select education",
             Self::SelectEmployment => "select employment",
+            Self::ScrollDetailsDown => "scroll details down",
+            Self::ScrollDetailsUp => "scroll details up",
+            Self::NextContactField => "next field",
+            Self::PreviousContactField => "previous field",
+            Self::CopyContactField => "copy",
+            Self::OpenContactLink => "open link",
             Self::ScrollAboutMeTextDown => "scroll down",
             Self::ScrollAboutMeTextUp => "scroll up",
-        }
-    }
-
-    pub fn key_binding(&self) -> &str {
-        match self {
-            Self::Quit => "q",
-            Self::SwitchToAboutMe => "a",
-            Self::SwitchToContactInformation => "c",
-            Self::SwitchToEmployment => "e",
-            Self::NextEmployer => "j",
-            Self::PreviousEmployer => "k",
-            Self::NextEducation => "j",
-            Self::PreviousEducation => "k",
-            Self::SelectEducation => "C-o",
-            Self::SelectEmployment => "C-o",
-            Self::ScrollAboutMeTextDown => "C-n",
-            Self::ScrollAboutMeTextUp => "C-p",
+            Self::FirstEntry => "first entry",
+            Self::LastEntry => "last entry",
+            Self::ScrollPageDown => "page down",
+            Self::ScrollPageUp => "page up",
+            Self::CloseHelp => "close",
+            Self::ScrollHelpDown => "scroll down",
+            Self::ScrollHelpUp => "scroll up",
         }
     }
 }
@@ -0,0 +1,191 @@
+use std::fmt::Write;
+
+use super::{history_sections, paragraphs, profile_sections, Block, Entry, Item};
+use crate::{assets::banner::ASCII_ART, resume::Resume, ui::theme::Theme};
+
+const STYLE: &str = r#"
+body { margin: 0; background: #0d1117; color: #e6e6e6; font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }
+main { max-width: 60rem; margin: 0 auto; padding: 1rem; }
+pre.banner { color: var(--accent); font-weight: bold; font-size: 0.5rem; line-height: 1.1; overflow-x: auto; text-align: center; }
+h1, h2, h3, strong { color: var(--accent); }
+a { color: var(--accent); }
+.tabs > input { display: none; }
+.tabs > label { display: inline-block; padding: 0.5rem 1rem; border: 1px solid #30363d; border-bottom: none; cursor: pointer; }
+.tabs > input:checked + label { color: var(--accent); font-weight: bold; }
+.tabs > section { display: none; border: 1px solid #30363d; padding: 1rem; }
+#tab-about:checked ~ .about, #tab-contact:checked ~ .contact, #tab-employment:checked ~ .employment { display: block; }
+.entry { margin-bottom: 1.5rem; }
+.dates, .muted { color: #8b949e; }
+table td { padding: 0.25rem 1rem 0.25rem 0; vertical-align: top; }
+@media print { .tabs > label { display: none; } .tabs > section { display: block; border: none; } }
+"#;
+
+/// Escapes text for use in both element content and quoted attributes.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn link(url: &str, text: &str) -> String {
+    format!("<a href=\"{}\">{}</a>", escape(url), escape(text))
+}
+
+fn render_entry(out: &mut String, entry: &Entry) {
+    let _ = writeln!(
+        out,
+        "<div class=\"entry\">\n<h3>{}</h3>",
+        escape(entry.title)
+    );
+    if !entry.subtitle.is_empty() {
+        let _ = writeln!(out, "<strong>{}</strong>", escape(entry.subtitle));
+    }
+    let meta = entry.meta();
+    if !meta.is_empty() {
+        let _ = writeln!(
+            out,
+            "<div class=\"dates\">{}</div>",
+            escape(&meta.join(" · "))
+        );
+    }
+    if let Some(url) = &entry.details.link {
+        let _ = writeln!(out, "<div>{}</div>", link(url, url));
+    }
+    if let Some(summary) = &entry.details.summary {
+        let _ = writeln!(out, "<p>{}</p>", escape(summary));
+    }
+    if !entry.details.achievements.is_empty() {
+        out.push_str("<ul>\n");
+        for achievement in &entry.details.achievements {
+            let _ = writeln!(out, "<li>{}</li>", escape(achievement));
+        }
+        out.push_str("</ul>\n");
+    }
+    if let Some(technologies) = entry.technologies() {
+        let _ = writeln!(
+            out,
+            "<p><strong>Technologies:</strong> {}</p>",
+            escape(&technologies)
+        );
+    }
+    out.push_str("</div>\n");
+}
+
+fn render_item(out: &mut String, item: &Item) {
+    let _ = write!(out, "<li><strong>{}</strong>", escape(item.title));
+    if let Some(note) = &item.note {
+        let _ = write!(out, " <span class=\"muted\">{}</span>", escape(note));
+    }
+    if let Some(detail) = &item.detail {
+        let _ = write!(out, ": {}", escape(detail));
+    }
+    out.push_str("</li>\n");
+}
+
+fn render_block(out: &mut String, heading: &str, block: &Block) {
+    let _ = writeln!(out, "<h2>{}</h2>", escape(heading));
+    match block {
+        Block::Entries(entries) => entries.iter().for_each(|entry| render_entry(out, entry)),
+        Block::Items(items) => {
+            out.push_str("<ul>\n");
+            items.iter().for_each(|item| render_item(out, item));
+            out.push_str("</ul>\n");
+        }
+    }
+}
+
+/// A single self-contained page with the same three tabs as the TUI. The
+/// tabs are plain CSS, so the page needs no scripts.
+pub fn render(resume: &Resume, theme: Theme) -> String {
+    let title = resume.profile.name.as_deref().unwrap_or("Resume");
+    let mut out = String::new();
+
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>:root {{ --accent: {}; }}{}</style>\n</head>\n<body>\n<main>",
+        escape(title),
+        theme.css_color(),
+        STYLE
+    );
+    let _ = writeln!(out, "<pre class=\"banner\">{}</pre>", escape(ASCII_ART));
+    if let Some(label) = &resume.profile.label {
+        let _ = writeln!(out, "<p class=\"muted\">{}</p>", escape(label));
+    }
+
+    out.push_str(
+        "<div class=\"tabs\">\n\
+         <input type=\"radio\" name=\"tab\" id=\"tab-about\" checked><label for=\"tab-about\">About me</label>\n\
+         <input type=\"radio\" name=\"tab\" id=\"tab-contact\"><label for=\"tab-contact\">Contact details</label>\n\
+         <input type=\"radio\" name=\"tab\" id=\"tab-employment\"><label for=\"tab-employment\">Employment &amp; Education</label>\n",
+    );
+
+    out.push_str("<section class=\"about\">\n");
+    for paragraph in paragraphs(&resume.about.about_me) {
+        let _ = writeln!(out, "<p>{}</p>", escape(paragraph));
+    }
+    for (heading, block) in profile_sections(&resume.profile) {
+        render_block(&mut out, heading, &block);
+    }
+    out.push_str("</section>\n");
+
+    out.push_str("<section class=\"contact\">\n<table>\n");
+    for field in resume.contact.fields() {
+        let value = match &field.url {
+            Some(url) => link(url, &field.value),
+            None => escape(&field.value),
+        };
+        let _ = writeln!(
+            out,
+            "<tr><td><strong>{}</strong></td><td>{}</td></tr>",
+            escape(&field.label),
+            value
+        );
+    }
+    out.push_str("</table>\n</section>\n");
+
+    out.push_str("<section class=\"employment\">\n");
+    for (heading, entries) in history_sections(resume) {
+        render_block(&mut out, heading, &Block::Entries(entries));
+    }
+    out.push_str("</section>\n</div>\n</main>\n</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contact::ContactDetails, employment::Employment};
+
+    #[test]
+    fn test_render_escapes_and_links() {
+        let resume = Resume {
+            contact: ContactDetails {
+                email: Some(String::from("me@example.com")),
+                ..ContactDetails::default()
+            },
+            employment: vec![Employment {
+                employer: String::from("Smith & <Sons>"),
+                ..Employment::_default()
+            }],
+            ..Resume::default()
+        };
+        let html = render(&resume, Theme::Blue);
+
+        assert!(html.contains("<h3>Smith &amp; &lt;Sons&gt;</h3>"));
+        assert!(html.contains("<a href=\"mailto:me@example.com\">me@example.com</a>"));
+        assert!(html.contains("--accent: #39c5cf"));
+        assert!(html.contains("<pre class=\"banner\">"));
+        assert_eq!(html.matches("<section").count(), 3);
+    }
+}
@@ -0,0 +1,205 @@
+use std::{error::Error, fs, io, path::Path};
+
+use minijinja::{context, syntax::SyntaxConfig, value::Value, Environment};
+
+use super::{date_range, paragraphs, PaperSize};
+use crate::resume::Resume;
+
+/// Templates compiled into the binary, by name.
+const BUILTIN_TEMPLATES: [(&str, &str); 2] = [
+    ("moderncv", include_str!("templates/moderncv.tex")),
+    ("article", include_str!("templates/article.tex")),
+];
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes a URL for `\href` and `\url`, which only need `%` and `#`
+/// protecting.
+fn url(value: String) -> Value {
+    Value::from_safe_string(value.replace('%', "\\%").replace('#', "\\#"))
+}
+
+/// Looks `name` up in `template_dir` first, as `<name>.tex`, so any built-in
+/// template can be replaced and new ones added without rebuilding. A
+/// `template_dir` that doesn't exist is an error rather than a silent fall
+/// back to the built-in templates.
+fn load_template(name: &str, template_dir: Option<&Path>) -> Result<String, Box<dyn Error>> {
+    if let Some(dir) = template_dir {
+        if !dir.is_dir() {
+            return Err(
+                format!("LaTeX template directory {} does not exist", dir.display()).into(),
+            );
+        }
+        let path = dir.join(format!("{}.tex", name));
+        match fs::read_to_string(&path) {
+            Ok(source) => return Ok(source),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(format!("{}: {}", path.display(), err).into()),
+        }
+    }
+
+    match BUILTIN_TEMPLATES
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+    {
+        Some((_, source)) => Ok(source.to_string()),
+        None => {
+            let mut message = format!(
+                "unknown LaTeX template {:?}, expected moderncv, article",
+                name
+            );
+            if let Some(dir) = template_dir {
+                message.push_str(&format!(" or a .tex file in {}", dir.display()));
+            }
+            Err(message.into())
+        }
+    }
+}
+
+fn environment() -> Result<Environment<'static>, Box<dyn Error>> {
+    let mut env = Environment::new();
+    // Jinja's braces clash with LaTeX's, so use angle brackets instead.
+    env.set_syntax(
+        SyntaxConfig::builder()
+            .block_delimiters("<%", "%>")
+            .variable_delimiters("<<", ">>")
+            .comment_delimiters("<#", "#>")
+            .build()?,
+    );
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_keep_trailing_newline(true);
+    env.set_formatter(|out, _state, value| {
+        if value.is_none() || value.is_undefined() {
+            return Ok(());
+        }
+        let text = value.to_string();
+        let _ = match value.is_safe() {
+            true => out.write_str(&text),
+            false => out.write_str(&escape(&text)),
+        };
+        Ok(())
+    });
+    env.add_filter("url", url);
+    env.add_function(
+        "date_range",
+        |start: Option<String>, end: Option<String>| date_range(start.as_deref(), end.as_deref()),
+    );
+    Ok(env)
+}
+
+pub fn render(
+    resume: &Resume,
+    paper: PaperSize,
+    template: &str,
+    template_dir: Option<&Path>,
+) -> Result<String, Box<dyn Error>> {
+    let source = load_template(template, template_dir)?;
+    let mut env = environment()?;
+    env.add_template_owned(template.to_string(), source)?;
+
+    let name = resume.profile.name.as_deref().unwrap_or_default();
+    let (first_name, last_name) = name.rsplit_once(' ').unwrap_or((name, ""));
+    let about: Vec<&str> = paragraphs(&resume.about.about_me).collect();
+
+    let rendered = env.get_template(template)?.render(context! {
+        resume => Value::from_serialize(resume),
+        paper => match paper {
+            PaperSize::A4 => "a4paper",
+            PaperSize::Letter => "letterpaper",
+        },
+        first_name,
+        last_name,
+        about,
+        contact_fields => Value::from_serialize(resume.contact.fields()),
+    })?;
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        contact::ContactDetails,
+        employment::{Details, Employment},
+        resume::Profile,
+    };
+
+    fn sample_resume() -> Resume {
+        Resume {
+            profile: Profile {
+                name: Some(String::from("Ada King Lovelace")),
+                ..Profile::default()
+            },
+            contact: ContactDetails {
+                website: Some(String::from("https://example.com/100%")),
+                ..ContactDetails::default()
+            },
+            employment: vec![Employment {
+                employer: String::from("Babbage & Co"),
+                start_date: String::from("1842-01"),
+                details: Details {
+                    achievements: vec![String::from("Wrote Note_G")],
+                    ..Details::default()
+                },
+                ..Employment::_default()
+            }],
+            ..Resume::default()
+        }
+    }
+
+    #[test]
+    fn test_builtin_templates_escape_values() {
+        let moderncv = render(&sample_resume(), PaperSize::A4, "moderncv", None).unwrap();
+        assert!(moderncv.contains("\\name{Ada King}{Lovelace}"));
+        assert!(moderncv.contains("\\cventry{Jan 1842 – end date}{role}{Babbage \\& Co}"));
+        assert!(moderncv.contains("\\item Wrote Note\\_G"));
+        assert!(!moderncv.contains("<<"));
+
+        let article = render(&sample_resume(), PaperSize::A4, "article", None).unwrap();
+        assert!(article.contains("\\href{https://example.com/100\\%}{https://example.com/100\\%}"));
+        assert!(article.contains("\\textbf{role}, Babbage \\& Co"));
+    }
+
+    #[test]
+    fn test_template_dir_overrides_and_extends() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("article.tex"), "custom << first_name >>").unwrap();
+        fs::write(
+            dir.join("mine.tex"),
+            "<% for job in resume.employment %><< job.employer >><% endfor %>",
+        )
+        .unwrap();
+
+        assert_eq!(
+            render(&sample_resume(), PaperSize::A4, "article", Some(dir)).unwrap(),
+            "custom Ada King"
+        );
+        assert_eq!(
+            render(&sample_resume(), PaperSize::A4, "mine", Some(dir)).unwrap(),
+            "Babbage \\& Co"
+        );
+        assert!(render(&sample_resume(), PaperSize::A4, "moderncv", Some(dir)).is_ok());
+        assert!(render(&sample_resume(), PaperSize::A4, "missing", Some(dir)).is_err());
+
+        let missing = dir.join("missing");
+        let err = render(&sample_resume(), PaperSize::A4, "moderncv", Some(&missing)).unwrap_err();
+        assert!(err.to_string().contains("does not exist"), "{}", err);
+    }
+}
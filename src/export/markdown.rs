@@ -0,0 +1,160 @@
+use std::fmt::Write;
+
+use super::{history_sections, paragraphs, profile_sections, Block, Entry, Item};
+use crate::resume::Resume;
+
+/// Escapes the characters that would otherwise start Markdown formatting.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn render_entry(out: &mut String, entry: &Entry) {
+    let _ = writeln!(out, "### {}\n", escape(&entry.headline()));
+
+    let mut meta = Vec::new();
+    let when_and_where = entry.meta();
+    if !when_and_where.is_empty() {
+        meta.push(format!("*{}*", escape(&when_and_where.join(" · "))));
+    }
+    if let Some(link) = &entry.details.link {
+        meta.push(format!("<{}>", link));
+    }
+    if !meta.is_empty() {
+        let _ = writeln!(out, "{}\n", meta.join(" · "));
+    }
+
+    if let Some(summary) = &entry.details.summary {
+        let _ = writeln!(out, "{}\n", escape(summary));
+    }
+    if !entry.details.achievements.is_empty() {
+        for achievement in &entry.details.achievements {
+            let _ = writeln!(out, "- {}", escape(achievement));
+        }
+        out.push('\n');
+    }
+    if let Some(technologies) = entry.technologies() {
+        let _ = writeln!(out, "**Technologies:** {}\n", escape(&technologies));
+    }
+}
+
+fn render_item(out: &mut String, item: &Item) {
+    let _ = write!(out, "- **{}**", escape(item.title));
+    if let Some(note) = &item.note {
+        let _ = write!(out, " ({})", escape(note));
+    }
+    if let Some(detail) = &item.detail {
+        let _ = write!(out, ": {}", escape(detail));
+    }
+    out.push('\n');
+}
+
+fn render_block(out: &mut String, heading: &str, block: &Block) {
+    let _ = writeln!(out, "## {}\n", heading);
+    match block {
+        Block::Entries(entries) => entries.iter().for_each(|entry| render_entry(out, entry)),
+        Block::Items(items) => {
+            items.iter().for_each(|item| render_item(out, item));
+            out.push('\n');
+        }
+    }
+}
+
+pub fn render(resume: &Resume) -> String {
+    let mut out = String::new();
+    let title = resume.profile.name.as_deref().unwrap_or("Resume");
+    let _ = writeln!(out, "# {}\n", escape(title));
+    if let Some(label) = &resume.profile.label {
+        let _ = writeln!(out, "*{}*\n", escape(label));
+    }
+    out.push_str(&render_sections(resume));
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// Renders the sections of `resume` without its title, for when it stands
+/// in for just one of them.
+pub fn render_sections(resume: &Resume) -> String {
+    let mut out = String::new();
+    let fields = resume.contact.fields();
+    if !fields.is_empty() {
+        out.push_str("## Contact\n\n");
+        for field in fields {
+            let value = match &field.url {
+                Some(url) => format!("[{}](<{}>)", escape(&field.value), url),
+                None => escape(&field.value),
+            };
+            let _ = writeln!(out, "- **{}:** {}", escape(&field.label), value);
+        }
+        out.push('\n');
+    }
+
+    let about: Vec<&str> = paragraphs(&resume.about.about_me).collect();
+    if !about.is_empty() {
+        out.push_str("## About me\n\n");
+        for paragraph in about {
+            let _ = writeln!(out, "{}\n", escape(paragraph));
+        }
+    }
+
+    for (heading, entries) in history_sections(resume) {
+        render_block(&mut out, heading, &Block::Entries(entries));
+    }
+    for (heading, block) in profile_sections(&resume.profile) {
+        render_block(&mut out, heading, &block);
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        contact::ContactDetails,
+        employment::{Details, Employment},
+    };
+
+    #[test]
+    fn test_render_markdown() {
+        let resume = Resume {
+            contact: ContactDetails {
+                email: Some(String::from("me@example.com")),
+                ..ContactDetails::default()
+            },
+            employment: vec![Employment {
+                employer: String::from("Acme"),
+                role: String::from("Engineer"),
+                start_date: String::from("2020-03"),
+                end_date: String::from("Present"),
+                details: Details {
+                    achievements: vec![String::from("Cut build times by 50%")],
+                    ..Details::default()
+                },
+            }],
+            ..Resume::default()
+        };
+
+        assert_eq!(
+            render(&resume),
+            "# Resume\n\n\
+             ## Contact\n\n\
+             - **Email:** [me@example.com](<mailto:me@example.com>)\n\n\
+             ## Employment\n\n\
+             ### Engineer, Acme\n\n\
+             *Mar 2020 – Present*\n\n\
+             - Cut build times by 50%\n"
+        );
+    }
+}
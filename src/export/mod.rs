@@ -0,0 +1,398 @@
+use std::{borrow::Cow, error::Error, path::PathBuf};
+
+use clap::{Args, ValueEnum};
+
+use crate::{
+    employment::{Details, Education, Employment},
+    resume::{Award, JsonResume, Profile, Project, Resume, Skill},
+    state::State,
+};
+
+mod html;
+mod latex;
+pub mod markdown;
+mod pdf;
+mod text;
+
+pub use pdf::{PaperSize, PdfFont};
+
+/// Fields come out in the order they are declared on the model types, so
+/// exports of the same resume always diff cleanly.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    /// This tool's own format, readable with `--source <file>`
+    Json,
+    /// The JSON Resume schema, https://jsonresume.org/schema
+    JsonResume,
+    Yaml,
+    Toml,
+    /// A self-contained web page with the same tabs as the TUI
+    Html,
+    /// A print-friendly document, written with `-o` or redirected
+    Pdf,
+    Markdown,
+    /// Plain text wrapped at `--width` columns
+    Text,
+    /// A `.tex` file rendered from `--template`
+    Latex,
+}
+
+/// Settings for the formats that lay out pages.
+#[derive(Debug, Clone, Args)]
+pub struct Options {
+    /// Page size for PDF output
+    #[arg(long, value_enum, default_value = "a4")]
+    pub paper: PaperSize,
+    /// Font for PDF output
+    #[arg(long, value_enum, default_value = "helvetica")]
+    pub font: PdfFont,
+    /// Column to wrap plain text output at
+    #[arg(long, default_value_t = 80, value_name = "COLUMNS")]
+    pub width: usize,
+    /// LaTeX template: moderncv, article or the name of a .tex file in the
+    /// configured template directory
+    #[arg(long, default_value = "moderncv", value_name = "NAME")]
+    pub template: String,
+    /// Where to look for LaTeX templates before the built-in ones. Set from
+    /// the `[latex]` config section rather than a flag.
+    #[arg(skip)]
+    pub template_dir: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            paper: PaperSize::A4,
+            font: PdfFont::Helvetica,
+            width: 80,
+            template: String::from("moderncv"),
+            template_dir: None,
+        }
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Shows ISO dates such as `2020-03` or `2020-03-14` as `Mar 2020`, and
+/// anything else as written.
+pub fn format_date(date: &str) -> String {
+    let mut parts = date.trim().splitn(3, '-');
+    let year = parts.next().filter(|year| year.len() == 4);
+    let month = parts.next().and_then(|month| month.parse::<usize>().ok());
+    match (year, month) {
+        (Some(year), Some(month @ 1..=12)) if year.parse::<u32>().is_ok() => {
+            format!("{} {}", MONTHS[month - 1], year)
+        }
+        _ => date.trim().to_string(),
+    }
+}
+
+/// `start – end`, or whichever of the two is known.
+pub fn date_range(start: Option<&str>, end: Option<&str>) -> String {
+    let dates: Vec<String> = [start, end]
+        .into_iter()
+        .flatten()
+        .filter(|date| !date.trim().is_empty())
+        .map(format_date)
+        .collect();
+    dates.join(" – ")
+}
+
+/// An employment, education or project as every format shows it. The
+/// backends only decide how each field is marked up.
+pub struct Entry<'a> {
+    /// The employer, educator or project name.
+    pub title: &'a str,
+    /// The role or qualification, if there is one.
+    pub subtitle: &'a str,
+    pub dates: String,
+    pub details: Cow<'a, Details>,
+}
+
+impl<'a> Entry<'a> {
+    fn job(job: &'a Employment) -> Self {
+        Self {
+            title: &job.employer,
+            subtitle: &job.role,
+            dates: date_range(Some(&job.start_date), Some(&job.end_date)),
+            details: Cow::Borrowed(&job.details),
+        }
+    }
+
+    fn course(course: &'a Education) -> Self {
+        Self {
+            title: &course.educator,
+            subtitle: &course.qualification,
+            dates: date_range(Some(&course.start_date), Some(&course.end_date)),
+            details: Cow::Borrowed(&course.details),
+        }
+    }
+
+    fn project(project: &'a Project) -> Self {
+        Self {
+            title: &project.name,
+            subtitle: "",
+            dates: date_range(project.start_date.as_deref(), project.end_date.as_deref()),
+            details: Cow::Owned(project.details()),
+        }
+    }
+
+    /// `role, employer`, for formats that put both on one line.
+    pub fn headline(&self) -> String {
+        match self.subtitle.is_empty() {
+            true => self.title.to_string(),
+            false => format!("{}, {}", self.subtitle, self.title),
+        }
+    }
+
+    /// The dates and location, whichever are known, in that order.
+    pub fn meta(&self) -> Vec<&str> {
+        [Some(self.dates.as_str()), self.details.location.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|meta| !meta.trim().is_empty())
+            .collect()
+    }
+
+    /// The technologies as one comma-separated list, if there are any.
+    pub fn technologies(&self) -> Option<String> {
+        match self.details.technologies.is_empty() {
+            true => None,
+            false => Some(self.details.technologies.join(", ")),
+        }
+    }
+}
+
+/// One line of a list of skills or awards: a title, a short note such as
+/// the level or awarder, and any further detail.
+pub struct Item<'a> {
+    pub title: &'a str,
+    pub note: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl<'a> Item<'a> {
+    fn skill(skill: &'a Skill) -> Self {
+        Self {
+            title: &skill.name,
+            note: skill.level.clone(),
+            detail: match skill.keywords.is_empty() {
+                true => None,
+                false => Some(skill.keywords.join(", ")),
+            },
+        }
+    }
+
+    fn award(award: &'a Award) -> Self {
+        let meta: Vec<&str> = [&award.awarder, &award.date]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        Self {
+            title: &award.title,
+            note: match meta.is_empty() {
+                true => None,
+                false => Some(meta.join(", ")),
+            },
+            detail: award.summary.clone(),
+        }
+    }
+
+    /// `title (note): detail`, leaving out whatever is missing.
+    pub fn plain(&self) -> String {
+        let mut text = self.title.to_string();
+        if let Some(note) = &self.note {
+            text = format!("{} ({})", text, note);
+        }
+        if let Some(detail) = &self.detail {
+            text = format!("{}: {}", text, detail);
+        }
+        text
+    }
+}
+
+/// What goes under a section heading.
+pub enum Block<'a> {
+    Entries(Vec<Entry<'a>>),
+    Items(Vec<Item<'a>>),
+}
+
+/// Employment then education, leaving out whichever is empty.
+pub fn history_sections(resume: &Resume) -> Vec<(&'static str, Vec<Entry<'_>>)> {
+    let employment = resume.employment.iter().map(Entry::job).collect();
+    let education = resume.education.iter().map(Entry::course).collect();
+    [("Employment", employment), ("Education", education)]
+        .into_iter()
+        .filter(|(_, entries): &(_, Vec<Entry>)| !entries.is_empty())
+        .collect()
+}
+
+/// Skills, projects then awards, leaving out whichever is empty.
+pub fn profile_sections(profile: &Profile) -> Vec<(&'static str, Block<'_>)> {
+    let mut sections = Vec::new();
+    if !profile.skills.is_empty() {
+        let items = profile.skills.iter().map(Item::skill).collect();
+        sections.push(("Skills", Block::Items(items)));
+    }
+    if !profile.projects.is_empty() {
+        let entries = profile.projects.iter().map(Entry::project).collect();
+        sections.push(("Projects", Block::Entries(entries)));
+    }
+    if !profile.awards.is_empty() {
+        let items = profile.awards.iter().map(Item::award).collect();
+        sections.push(("Awards", Block::Items(items)));
+    }
+    sections
+}
+
+/// The non-empty paragraphs of the about me text, trimmed.
+pub fn paragraphs(text: &str) -> impl Iterator<Item = &str> {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+}
+
+impl Format {
+    /// Whether the output is text rather than binary.
+    pub fn is_text(&self) -> bool {
+        !matches!(self, Format::Pdf)
+    }
+}
+
+pub fn render(state: &State, format: Format, options: &Options) -> Result<Vec<u8>, Box<dyn Error>> {
+    let resume = &state.resume;
+    let text = match format {
+        Format::Json => serde_json::to_string_pretty(resume)? + "\n",
+        Format::JsonResume => serde_json::to_string_pretty(&JsonResume::from(resume))? + "\n",
+        Format::Yaml => serde_yaml::to_string(resume)?,
+        Format::Toml => toml::to_string_pretty(resume)?,
+        Format::Html => html::render(resume, state.theme),
+        Format::Pdf => return Ok(pdf::render(resume, options.paper, options.font)),
+        Format::Markdown => markdown::render(resume),
+        Format::Text => text::render(resume, options.width),
+        Format::Latex => latex::render(
+            resume,
+            options.paper,
+            &options.template,
+            options.template_dir.as_deref(),
+        )?,
+    };
+    Ok(text.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        employment::{Education, Employment},
+        resume::{Profile, Skill},
+    };
+
+    fn text(state: &State, format: Format) -> String {
+        String::from_utf8(render(state, format, &Options::default()).unwrap()).unwrap()
+    }
+
+    fn sample_state() -> State {
+        let mut state = State::default();
+        state.resume = Resume {
+            profile: Profile {
+                name: Some(String::from("Ada")),
+                skills: vec![Skill {
+                    name: String::from("Rust"),
+                    ..Skill::default()
+                }],
+                ..Profile::default()
+            },
+            employment: vec![Employment::_default()],
+            education: vec![Education::_default()],
+            ..Resume::default()
+        };
+        state
+    }
+
+    #[test]
+    fn test_date_range() {
+        assert_eq!(
+            date_range(Some("2019-09-01"), Some("Present")),
+            "Sep 2019 – Present"
+        );
+        assert_eq!(date_range(Some("2019"), Some("")), "2019");
+        assert_eq!(date_range(None, Some("2021-13")), "2021-13");
+    }
+
+    #[test]
+    fn test_shared_layout() {
+        let mut resume = sample_state().resume;
+        resume.profile.skills[0].level = Some(String::from("Expert"));
+        resume.profile.awards = vec![Award {
+            title: String::from("Prize"),
+            awarder: Some(String::from("Society")),
+            date: Some(String::from("1843")),
+            ..Award::default()
+        }];
+
+        let profile = profile_sections(&resume.profile);
+        let headings: Vec<&str> = profile.iter().map(|(heading, _)| *heading).collect();
+        assert_eq!(headings, vec!["Skills", "Awards"]);
+        match &profile[1].1 {
+            Block::Items(items) => assert_eq!(items[0].plain(), "Prize (Society, 1843)"),
+            Block::Entries(_) => panic!("expected awards to be items"),
+        }
+
+        let history = history_sections(&resume);
+        assert_eq!(history.len(), 2);
+        let job = &history[0].1[0];
+        assert_eq!(job.headline(), "role, default");
+        assert_eq!(job.meta(), vec!["start date – end date"]);
+    }
+
+    #[test]
+    fn test_every_format_renders() {
+        let state = sample_state();
+        for format in Format::value_variants() {
+            let rendered = render(&state, *format, &Options::default()).unwrap();
+            let rendered = String::from_utf8_lossy(&rendered);
+            assert!(rendered.contains("Ada"), "{:?}: {}", format, rendered);
+        }
+    }
+
+    #[test]
+    fn test_field_order_is_stable() {
+        let yaml = text(&sample_state(), Format::Yaml);
+        let keys: Vec<&str> = yaml
+            .lines()
+            .filter(|line| !line.starts_with([' ', '-']))
+            .filter_map(|line| line.split(':').next())
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "name",
+                "skills",
+                "about",
+                "contact",
+                "employment",
+                "education"
+            ]
+        );
+
+        let toml = text(&sample_state(), Format::Toml);
+        let employer = toml.find("employer = \"default\"").unwrap();
+        let role = toml.find("role = \"role\"").unwrap();
+        assert!(employer < role);
+    }
+
+    #[test]
+    fn test_yaml_and_toml_round_trip() {
+        let state = sample_state();
+        let from_yaml: Resume = serde_yaml::from_str(&text(&state, Format::Yaml)).unwrap();
+        let from_toml: Resume = toml::from_str(&text(&state, Format::Toml)).unwrap();
+
+        let expected = serde_json::to_value(&state.resume).unwrap();
+        assert_eq!(serde_json::to_value(&from_yaml).unwrap(), expected);
+        assert_eq!(serde_json::to_value(&from_toml).unwrap(), expected);
+    }
+}
@@ -0,0 +1,553 @@
+use clap::ValueEnum;
+use pdf_writer::{
+    types::{ActionType, AnnotationType},
+    Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr,
+};
+
+use super::{history_sections, paragraphs, profile_sections, Block, Entry};
+use crate::resume::Resume;
+
+const MARGIN: f32 = 56.7; // 20mm
+const BODY_SIZE: f32 = 10.0;
+const LEADING: f32 = 1.35;
+const REGULAR: Name = Name(b"F1");
+const BOLD: Name = Name(b"F2");
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum PaperSize {
+    A4,
+    Letter,
+}
+
+impl PaperSize {
+    /// Width and height in points.
+    fn dimensions(&self) -> (f32, f32) {
+        match self {
+            PaperSize::A4 => (595.28, 841.89),
+            PaperSize::Letter => (612.0, 792.0),
+        }
+    }
+}
+
+/// One of the standard PDF fonts, which every viewer has, so nothing needs
+/// embedding.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum PdfFont {
+    Helvetica,
+    Times,
+    Courier,
+}
+
+impl PdfFont {
+    fn base_font(&self, bold: bool) -> Name<'static> {
+        match (self, bold) {
+            (PdfFont::Helvetica, false) => Name(b"Helvetica"),
+            (PdfFont::Helvetica, true) => Name(b"Helvetica-Bold"),
+            (PdfFont::Times, false) => Name(b"Times-Roman"),
+            (PdfFont::Times, true) => Name(b"Times-Bold"),
+            (PdfFont::Courier, false) => Name(b"Courier"),
+            (PdfFont::Courier, true) => Name(b"Courier-Bold"),
+        }
+    }
+
+    /// Width of `text` in points. Courier is exact; the proportional fonts
+    /// use Helvetica's metrics, scaled and rounded up so wrapped lines never
+    /// run into the margin.
+    fn text_width(&self, text: &str, size: f32, bold: bool) -> f32 {
+        let units: f32 = match self {
+            PdfFont::Courier => text.chars().count() as f32 * 600.0,
+            PdfFont::Helvetica | PdfFont::Times => text.chars().map(helvetica_width).sum(),
+        };
+        let scale = match (self, bold) {
+            (PdfFont::Courier, _) => 1.0,
+            (PdfFont::Helvetica, false) => 1.0,
+            (PdfFont::Helvetica, true) => 1.08,
+            (PdfFont::Times, false) => 0.95,
+            (PdfFont::Times, true) => 1.02,
+        };
+        units * scale * size / 1000.0
+    }
+}
+
+/// Glyph widths of Helvetica in thousandths of an em.
+fn helvetica_width(c: char) -> f32 {
+    match c {
+        ' ' | '!' | ',' | '.' | '/' | ':' | ';' | '[' | '\\' | ']' | 'I' | 'f' | 't' => 278.0,
+        'i' | 'j' | 'l' => 222.0,
+        '\'' => 191.0,
+        '"' => 355.0,
+        '(' | ')' | '-' | '`' | 'r' => 333.0,
+        '*' => 389.0,
+        '^' => 469.0,
+        '|' => 260.0,
+        '{' | '}' => 334.0,
+        '+' | '<' | '=' | '>' | '~' => 584.0,
+        '%' => 889.0,
+        '@' => 1015.0,
+        'J' | 'c' | 'k' | 's' | 'v' | 'x' | 'y' | 'z' => 500.0,
+        'F' | 'T' | 'Z' => 611.0,
+        'A' | 'B' | 'E' | 'K' | 'P' | 'S' | 'V' | 'X' | 'Y' | '&' => 667.0,
+        'C' | 'D' | 'H' | 'N' | 'R' | 'U' | 'w' => 722.0,
+        'G' | 'O' | 'Q' => 778.0,
+        'M' | 'm' => 833.0,
+        'W' => 944.0,
+        'L' => 556.0,
+        _ if c.is_ascii() => 556.0,
+        // Wider than most glyphs, to be safe with anything unusual.
+        _ => 722.0,
+    }
+}
+
+/// Encodes `text` as WinAnsi, which the standard fonts use. Anything the
+/// encoding can't represent becomes `?`.
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '€' => 0x80,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            c if (c as u32) < 0x80 || (0xa0..=0xff).contains(&(c as u32)) => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+struct Style {
+    size: f32,
+    bold: bool,
+    gray: f32,
+}
+
+impl Style {
+    const BODY: Style = Style {
+        size: BODY_SIZE,
+        bold: false,
+        gray: 0.0,
+    };
+    const STRONG: Style = Style {
+        bold: true,
+        ..Style::BODY
+    };
+    const MUTED: Style = Style {
+        gray: 0.4,
+        ..Style::BODY
+    };
+    const HEADING: Style = Style {
+        size: 14.0,
+        bold: true,
+        gray: 0.0,
+    };
+    const TITLE: Style = Style {
+        size: 22.0,
+        bold: true,
+        gray: 0.0,
+    };
+}
+
+struct Page {
+    content: Content,
+    links: Vec<(Rect, String)>,
+}
+
+/// Lays text out top to bottom, starting a new page whenever the next line
+/// would cross the bottom margin.
+struct Layout {
+    font: PdfFont,
+    width: f32,
+    height: f32,
+    pages: Vec<Page>,
+    y: f32,
+}
+
+impl Layout {
+    fn new(paper: PaperSize, font: PdfFont) -> Self {
+        let (width, height) = paper.dimensions();
+        let mut layout = Self {
+            font,
+            width,
+            height,
+            pages: Vec::new(),
+            y: 0.0,
+        };
+        layout.new_page();
+        layout
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(Page {
+            content: Content::new(),
+            links: Vec::new(),
+        });
+        self.y = self.height - MARGIN;
+    }
+
+    fn text_width(&self) -> f32 {
+        self.width - 2.0 * MARGIN
+    }
+
+    /// Starts a new page unless `height` more points fit on this one.
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    /// Writes one line at `indent` and returns the rectangle it covers.
+    fn line(&mut self, indent: f32, text: &str, style: Style) -> Rect {
+        let leading = style.size * LEADING;
+        self.ensure_space(leading);
+        self.y -= leading;
+
+        let x = MARGIN + indent;
+        let baseline = self.y + style.size * 0.3;
+        let font = match style.bold {
+            true => BOLD,
+            false => REGULAR,
+        };
+        let page = self.pages.last_mut().unwrap();
+        page.content
+            .begin_text()
+            .set_fill_gray(style.gray)
+            .set_font(font, style.size)
+            .next_line(x, baseline)
+            .show(Str(&encode(text)))
+            .end_text();
+
+        let width = self.font.text_width(text, style.size, style.bold);
+        Rect::new(x, self.y, x + width, self.y + leading)
+    }
+
+    /// Wraps `text` like a paragraph, making every line of it a link to
+    /// `url`, so long URLs stay inside the margin.
+    fn link(&mut self, indent: f32, text: &str, url: &str, style: Style) {
+        for line in self.wrap(indent, text, style) {
+            let rect = self.line(indent, &line, style);
+            let page = self.pages.last_mut().unwrap();
+            page.links.push((rect, url.to_string()));
+        }
+    }
+
+    /// Breaks `text` into lines no wider than the page allows, splitting
+    /// words only when a single word is too long for a line.
+    fn wrap(&self, indent: f32, text: &str, style: Style) -> Vec<String> {
+        let max = self.text_width() - indent;
+        let fits = |line: &str| self.font.text_width(line, style.size, style.bold) <= max;
+
+        let mut lines = Vec::new();
+        let mut current = String::new();
+        for word in text.split_whitespace() {
+            let candidate = match current.is_empty() {
+                true => word.to_string(),
+                false => format!("{} {}", current, word),
+            };
+            if fits(&candidate) {
+                current = candidate;
+                continue;
+            }
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            for c in word.chars() {
+                current.push(c);
+                if !fits(&current) {
+                    current.pop();
+                    lines.push(std::mem::replace(&mut current, c.to_string()));
+                }
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+        lines
+    }
+
+    fn paragraph(&mut self, indent: f32, text: &str, style: Style) {
+        for line in self.wrap(indent, text, style) {
+            self.line(indent, &line, style);
+        }
+    }
+
+    fn bullet(&mut self, text: &str) {
+        let indent = 12.0;
+        for (i, line) in self.wrap(indent, text, Style::BODY).iter().enumerate() {
+            if i == 0 {
+                // Make the page break, if any, before drawing the bullet so
+                // it lands on the same line as its text.
+                self.ensure_space(Style::BODY.size * LEADING);
+                let y = self.y;
+                self.line(0.0, "•", Style::BODY);
+                self.y = y;
+            }
+            self.line(indent, line, Style::BODY);
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        // Keep a heading with at least a couple of lines of what follows.
+        self.ensure_space(Style::HEADING.size * LEADING + 3.0 * BODY_SIZE * LEADING);
+        self.gap(BODY_SIZE);
+        self.line(0.0, text, Style::HEADING);
+
+        let y = self.y - 2.0;
+        let right = self.width - MARGIN;
+        let page = self.pages.last_mut().unwrap();
+        page.content
+            .set_stroke_gray(0.6)
+            .set_line_width(0.5)
+            .move_to(MARGIN, y)
+            .line_to(right, y)
+            .stroke();
+        self.gap(6.0);
+    }
+
+    fn entry(&mut self, entry: &Entry) {
+        self.ensure_space(4.0 * BODY_SIZE * LEADING);
+        self.gap(BODY_SIZE * 0.5);
+        self.paragraph(0.0, entry.title, Style::STRONG);
+        if !entry.subtitle.is_empty() {
+            self.paragraph(0.0, entry.subtitle, Style::BODY);
+        }
+        let meta = entry.meta();
+        if !meta.is_empty() {
+            self.paragraph(0.0, &meta.join(" · "), Style::MUTED);
+        }
+        if let Some(url) = &entry.details.link {
+            self.link(0.0, url, url, Style::MUTED);
+        }
+        if let Some(summary) = &entry.details.summary {
+            self.gap(BODY_SIZE * 0.3);
+            self.paragraph(0.0, summary, Style::BODY);
+        }
+        for achievement in &entry.details.achievements {
+            self.bullet(achievement);
+        }
+        if let Some(technologies) = entry.technologies() {
+            self.paragraph(
+                0.0,
+                &format!("Technologies: {}", technologies),
+                Style::MUTED,
+            );
+        }
+    }
+
+    fn block(&mut self, heading: &str, block: &Block) {
+        self.heading(heading);
+        match block {
+            Block::Entries(entries) => entries.iter().for_each(|entry| self.entry(entry)),
+            Block::Items(items) => items.iter().for_each(|item| self.bullet(&item.plain())),
+        }
+    }
+
+    fn resume(&mut self, resume: &Resume) {
+        let title = resume.profile.name.as_deref().unwrap_or("Resume");
+        self.paragraph(0.0, title, Style::TITLE);
+        if let Some(label) = &resume.profile.label {
+            self.paragraph(0.0, label, Style::MUTED);
+        }
+        self.gap(BODY_SIZE * 0.5);
+        for field in resume.contact.fields() {
+            let text = format!("{}: {}", field.label, field.value);
+            match &field.url {
+                Some(url) => self.link(0.0, &text, url, Style::BODY),
+                None => self.paragraph(0.0, &text, Style::BODY),
+            }
+        }
+
+        let about: Vec<&str> = paragraphs(&resume.about.about_me).collect();
+        if !about.is_empty() {
+            self.heading("About me");
+            for paragraph in about {
+                self.paragraph(0.0, paragraph, Style::BODY);
+                self.gap(BODY_SIZE * 0.5);
+            }
+        }
+
+        for (heading, entries) in history_sections(resume) {
+            self.block(heading, &Block::Entries(entries));
+        }
+        for (heading, block) in profile_sections(&resume.profile) {
+            self.block(heading, &block);
+        }
+    }
+
+    fn page_numbers(&mut self) {
+        let count = self.pages.len();
+        for (i, page) in self.pages.iter_mut().enumerate() {
+            let text = format!("{} / {}", i + 1, count);
+            let width = self.font.text_width(&text, 8.0, false);
+            page.content
+                .begin_text()
+                .set_fill_gray(0.4)
+                .set_font(REGULAR, 8.0)
+                .next_line((self.width - width) / 2.0, MARGIN / 2.0)
+                .show(Str(text.as_bytes()))
+                .end_text();
+        }
+    }
+
+    fn finish(mut self, title: &str) -> Vec<u8> {
+        self.page_numbers();
+
+        let catalog_id = Ref::new(1);
+        let page_tree_id = Ref::new(2);
+        let regular_id = Ref::new(3);
+        let bold_id = Ref::new(4);
+        let info_id = Ref::new(5);
+        let mut next_id = 6;
+        let mut alloc = || {
+            next_id += 1;
+            Ref::new(next_id - 1)
+        };
+        let page_ids: Vec<(Ref, Ref)> = self.pages.iter().map(|_| (alloc(), alloc())).collect();
+
+        let mut pdf = Pdf::new();
+        pdf.catalog(catalog_id).pages(page_tree_id);
+        pdf.pages(page_tree_id)
+            .kids(page_ids.iter().map(|(page_id, _)| *page_id))
+            .count(page_ids.len() as i32);
+        pdf.document_info(info_id).title(TextStr(title));
+        for (id, bold) in [(regular_id, false), (bold_id, true)] {
+            pdf.type1_font(id)
+                .base_font(self.font.base_font(bold))
+                .encoding_predefined(Name(b"WinAnsiEncoding"));
+        }
+
+        for (page, (page_id, content_id)) in self.pages.into_iter().zip(page_ids) {
+            let mut writer = pdf.page(page_id);
+            writer
+                .media_box(Rect::new(0.0, 0.0, self.width, self.height))
+                .parent(page_tree_id)
+                .contents(content_id);
+            writer
+                .resources()
+                .fonts()
+                .pair(REGULAR, regular_id)
+                .pair(BOLD, bold_id);
+            if !page.links.is_empty() {
+                let mut annotations = writer.annotations();
+                for (rect, url) in &page.links {
+                    let mut annotation = annotations.push();
+                    annotation
+                        .subtype(AnnotationType::Link)
+                        .rect(*rect)
+                        .border(0.0, 0.0, 0.0, None);
+                    annotation
+                        .action()
+                        .action_type(ActionType::Uri)
+                        .uri(Str(url.as_bytes()));
+                }
+            }
+            writer.finish();
+            pdf.stream(content_id, &page.content.finish());
+        }
+        pdf.finish()
+    }
+}
+
+pub fn render(resume: &Resume, paper: PaperSize, font: PdfFont) -> Vec<u8> {
+    let mut layout = Layout::new(paper, font);
+    layout.resume(resume);
+    layout.finish(resume.profile.name.as_deref().unwrap_or("Resume"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        contact::ContactDetails,
+        employment::{Details, Employment},
+    };
+
+    fn resume_with_jobs(count: usize) -> Resume {
+        Resume {
+            contact: ContactDetails {
+                github: Some(String::from("ada")),
+                ..ContactDetails::default()
+            },
+            employment: vec![
+                Employment {
+                    details: Details {
+                        achievements: vec![String::from("Shipped things. ").repeat(20)],
+                        ..Details::default()
+                    },
+                    ..Employment::_default()
+                };
+                count
+            ],
+            ..Resume::default()
+        }
+    }
+
+    fn page_count(pdf: &[u8]) -> usize {
+        String::from_utf8_lossy(pdf)
+            .matches("/Type /Page\n")
+            .count()
+    }
+
+    #[test]
+    fn test_render_pdf_with_links() {
+        let pdf = render(&resume_with_jobs(1), PaperSize::A4, PdfFont::Helvetica);
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(text.contains("/BaseFont /Helvetica-Bold"));
+        assert!(text.contains("/URI (https://github.com/ada)"));
+        assert!(text.contains("/MediaBox [0 0 595.28 841.89]"));
+    }
+
+    #[test]
+    fn test_long_resumes_break_across_pages() {
+        assert_eq!(
+            page_count(&render(
+                &resume_with_jobs(1),
+                PaperSize::Letter,
+                PdfFont::Times
+            )),
+            1
+        );
+        assert!(
+            page_count(&render(
+                &resume_with_jobs(30),
+                PaperSize::Letter,
+                PdfFont::Courier
+            )) > 1
+        );
+    }
+
+    #[test]
+    fn test_long_links_wrap() {
+        let url = format!("https://example.com/{}", "a".repeat(300));
+        let mut layout = Layout::new(PaperSize::A4, PdfFont::Helvetica);
+        layout.link(0.0, &url, &url, Style::MUTED);
+
+        let links = &layout.pages[0].links;
+        assert!(links.len() > 1);
+        for (rect, target) in links {
+            assert_eq!(target, &url);
+            assert!(rect.x2 <= layout.width - MARGIN);
+        }
+    }
+
+    #[test]
+    fn test_wrap_fits_the_page() {
+        let layout = Layout::new(PaperSize::A4, PdfFont::Helvetica);
+        let text = "word ".repeat(200);
+        let lines = layout.wrap(0.0, &text, Style::BODY);
+
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert!(PdfFont::Helvetica.text_width(line, BODY_SIZE, false) <= layout.text_width());
+        }
+        assert_eq!(encode("a • b é"), b"a \x95 b \xe9");
+    }
+}
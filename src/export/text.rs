@@ -0,0 +1,173 @@
+use super::{history_sections, paragraphs, profile_sections, Block, Entry};
+use crate::resume::Resume;
+
+/// Greedily wraps `text` to `width` columns, starting the first line with
+/// `first` and the rest with `rest`. Words longer than a line are left
+/// whole rather than split.
+fn wrap(text: &str, width: usize, first: &str, rest: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = first.to_string();
+    let mut empty = true;
+    for word in text.split_whitespace() {
+        let fits = line.chars().count() + 1 + word.chars().count() <= width;
+        if !empty && !fits {
+            lines.push(std::mem::replace(&mut line, rest.to_string()));
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        empty = false;
+    }
+    if !empty {
+        lines.push(line);
+    }
+    lines
+}
+
+struct Writer {
+    width: usize,
+    out: String,
+}
+
+impl Writer {
+    fn line(&mut self, text: &str) {
+        self.out.push_str(text.trim_end());
+        self.out.push('\n');
+    }
+
+    fn blank(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn wrapped(&mut self, text: &str, first: &str, rest: &str) {
+        for line in wrap(text, self.width, first, rest) {
+            self.line(&line);
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.blank();
+        self.line(&text.to_uppercase());
+        self.line(&"=".repeat(text.chars().count()));
+        self.blank();
+    }
+
+    fn entry(&mut self, entry: &Entry) {
+        self.blank();
+        self.wrapped(&entry.headline(), "", "  ");
+
+        let mut meta = entry.meta();
+        meta.extend(entry.details.link.as_deref());
+        if !meta.is_empty() {
+            self.wrapped(&meta.join(" · "), "", "");
+        }
+        if let Some(summary) = &entry.details.summary {
+            self.wrapped(summary, "  ", "  ");
+        }
+        for achievement in &entry.details.achievements {
+            self.wrapped(achievement, "  • ", "    ");
+        }
+        if let Some(technologies) = entry.technologies() {
+            self.wrapped(&format!("Technologies: {}", technologies), "  ", "  ");
+        }
+    }
+
+    fn block(&mut self, heading: &str, block: &Block) {
+        self.heading(heading);
+        match block {
+            Block::Entries(entries) => entries.iter().for_each(|entry| self.entry(entry)),
+            Block::Items(items) => {
+                for item in items {
+                    self.wrapped(&item.plain(), "• ", "  ");
+                }
+            }
+        }
+    }
+}
+
+/// Plain text wrapped at `width` columns, for pasting into forms that
+/// don't take any formatting.
+pub fn render(resume: &Resume, width: usize) -> String {
+    let mut writer = Writer {
+        width,
+        out: String::new(),
+    };
+
+    let title = resume.profile.name.as_deref().unwrap_or("Resume");
+    writer.wrapped(title, "", "");
+    if let Some(label) = &resume.profile.label {
+        writer.wrapped(label, "", "");
+    }
+
+    let fields = resume.contact.fields();
+    if !fields.is_empty() {
+        writer.heading("Contact");
+        let label_width = fields
+            .iter()
+            .map(|field| field.label.chars().count())
+            .max()
+            .unwrap_or(0);
+        for field in fields {
+            let first = format!("{:width$}  ", field.label, width = label_width);
+            let rest = " ".repeat(first.chars().count());
+            writer.wrapped(&field.value, &first, &rest);
+        }
+    }
+
+    let about: Vec<&str> = paragraphs(&resume.about.about_me).collect();
+    if !about.is_empty() {
+        writer.heading("About me");
+        for paragraph in about {
+            writer.blank();
+            writer.wrapped(paragraph, "", "");
+        }
+    }
+
+    for (heading, entries) in history_sections(resume) {
+        writer.block(heading, &Block::Entries(entries));
+    }
+    for (heading, block) in profile_sections(&resume.profile) {
+        writer.block(heading, &block);
+    }
+    writer.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::employment::{Details, Employment};
+
+    #[test]
+    fn test_wrap_hangs_indent() {
+        assert_eq!(
+            wrap("one two three four five", 12, "  • ", "    "),
+            vec!["  • one two", "    three", "    four", "    five"]
+        );
+        assert_eq!(
+            wrap("unbreakable-word", 5, "", ""),
+            vec!["unbreakable-word"]
+        );
+    }
+
+    #[test]
+    fn test_render_text_respects_width() {
+        let resume = Resume {
+            employment: vec![Employment {
+                details: Details {
+                    summary: Some("lorem ipsum dolor ".repeat(20)),
+                    ..Details::default()
+                },
+                ..Employment::_default()
+            }],
+            ..Resume::default()
+        };
+
+        let text = render(&resume, 40);
+        assert!(text.contains("EMPLOYMENT\n==========\n\nrole, default\nstart date – end date\n"));
+        assert!(text.lines().all(|line| line.chars().count() <= 40));
+    }
+}
@@ -1,52 +1,945 @@
-use crossterm::event::{self, KeyEvent};
+use std::{collections::BTreeMap, str::FromStr};
 
-use crate::{employment::EmploymentEducation, state::State, ui::tabs::TabsHeadings};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use tui::{
+    layout::Rect,
+    widgets::{Block, Borders},
+};
 
-pub fn global_key_map(key: KeyEvent, state: &mut State) {
-    match key.code {
-        event::KeyCode::Char('e') => state.set_selected_tab(TabsHeadings::EmploymentAndEducation),
-        event::KeyCode::Char('a') => state.set_selected_tab(TabsHeadings::AboutMe),
-        event::KeyCode::Char('c') => state.set_selected_tab(TabsHeadings::ContactDetails),
-        _ => {}
+use crate::{
+    employment::EmploymentEducation,
+    events::{Context, Event},
+    state::State,
+    ui::{
+        self, about_me, employment, help,
+        tabs::{self, TabsHeadings},
+    },
+};
+
+/// How many lines one notch of the mouse wheel scrolls.
+const WHEEL_LINES: u16 = 3;
+
+/// Parses one key as shown in the footer, e.g. `q`, `C-o`, `S-Tab` or
+/// `PageDown`. Modifiers are `C-` (control), `M-` or `A-` (alt) and `S-`
+/// (shift).
+pub fn parse_key(notation: &str) -> Result<KeyEvent, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut key = notation;
+    while let Some((prefix, rest)) = key.split_at_checked(2) {
+        let modifier = match prefix {
+            "C-" => KeyModifiers::CONTROL,
+            "M-" | "A-" => KeyModifiers::ALT,
+            "S-" => KeyModifiers::SHIFT,
+            _ => break,
+        };
+        if rest.is_empty() {
+            break;
+        }
+        modifiers |= modifier;
+        key = rest;
     }
 
-    match state.selected_tab {
-        TabsHeadings::EmploymentAndEducation => employment_keymap(key, state),
-        TabsHeadings::AboutMe => about_me_keymap(key, state),
-        _ => {}
+    let mut chars = key.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+            KeyCode::Char(c.to_ascii_uppercase())
+        }
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match key.to_ascii_lowercase().as_str() {
+            // Terminals send Shift-Tab as a key of its own.
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "enter" | "return" | "ret" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "space" | "spc" => KeyCode::Char(' '),
+            "backspace" | "bs" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" | "pgup" => KeyCode::PageUp,
+            "pagedown" | "pgdn" => KeyCode::PageDown,
+            name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(format!("cannot parse key {:?}", notation)),
+            },
+        },
+    };
+    Ok(KeyEvent::new(code, modifiers))
+}
+
+/// Parses a key, or a sequence of keys pressed one after the other such as
+/// `g g` or `C-x C-c`.
+pub fn parse_chord(notation: &str) -> Result<Vec<KeyEvent>, String> {
+    let keys = notation
+        .split_whitespace()
+        .map(parse_key)
+        .collect::<Result<Vec<_>, _>>()?;
+    match keys.is_empty() {
+        true => Err(format!("cannot parse key {:?}", notation)),
+        false => Ok(keys),
     }
 }
 
-fn employment_keymap(key: KeyEvent, state: &mut State) {
-    match state.employment_or_education {
-        EmploymentEducation::Employment => match key.code {
-            event::KeyCode::Char('j') => state.next_employer(),
-            event::KeyCode::Char('k') => state.previous_employer(),
-            _ => {}
-        },
-        EmploymentEducation::Education => match key.code {
-            event::KeyCode::Char('j') => state.next_educator(),
-            event::KeyCode::Char('k') => state.previous_educator(),
-            _ => {}
+fn same_key(a: &KeyEvent, b: &KeyEvent) -> bool {
+    a.code == b.code && a.modifiers - KeyModifiers::SHIFT == b.modifiers - KeyModifiers::SHIFT
+}
+
+/// Whether `keys` starts with `prefix`.
+fn starts_with(keys: &[KeyEvent], prefix: &[KeyEvent]) -> bool {
+    keys.len() >= prefix.len() && keys.iter().zip(prefix).all(|(a, b)| same_key(a, b))
+}
+
+/// A ready-made set of bindings, chosen with `preset` under `[keybindings]`
+/// and then adjusted by the rest of the table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Preset {
+    Default,
+    Vim,
+    Emacs,
+    Arrows,
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(Preset::Default),
+            "vim" => Ok(Preset::Vim),
+            "emacs" => Ok(Preset::Emacs),
+            "arrows" => Ok(Preset::Arrows),
+            _ => Err(format!(
+                "unknown key preset {:?}, expected default, vim, emacs or arrows",
+                s
+            )),
+        }
+    }
+}
+
+impl Preset {
+    pub fn keys(&self, event: Event) -> &'static [&'static str] {
+        match (self, event) {
+            (Preset::Emacs, Event::Quit) => &["C-x C-c"],
+            (Preset::Emacs, Event::SwitchToAboutMe) => &["C-c a", "1"],
+            (Preset::Emacs, Event::SwitchToContactInformation) => &["C-c c", "2"],
+            (Preset::Emacs, Event::SwitchToEmployment) => &["C-c e", "3"],
+            (Preset::Emacs, Event::Retry) => &["g"],
+            (Preset::Emacs, Event::SelectEducation | Event::SelectEmployment) => &["C-x o"],
+            (
+                Preset::Emacs,
+                Event::NextEmployer
+                | Event::NextEducation
+                | Event::NextContactField
+                | Event::ScrollAboutMeTextDown,
+            ) => &["C-n", "Down"],
+            (
+                Preset::Emacs,
+                Event::PreviousEmployer
+                | Event::PreviousEducation
+                | Event::PreviousContactField
+                | Event::ScrollAboutMeTextUp,
+            ) => &["C-p", "Up"],
+            (Preset::Emacs, Event::ScrollDetailsDown) => &["M-n"],
+            (Preset::Emacs, Event::ScrollDetailsUp) => &["M-p"],
+            (Preset::Emacs, Event::ScrollPageDown) => &["C-v", "PageDown"],
+            (Preset::Emacs, Event::ScrollPageUp) => &["M-v", "PageUp"],
+            (Preset::Emacs, Event::FirstEntry) => &["M-<", "Home"],
+            (Preset::Emacs, Event::LastEntry) => &["M->", "End"],
+            (Preset::Emacs, Event::CopyContactField) => &["M-w"],
+            (Preset::Emacs, Event::OpenContactLink) => &["C-c C-o"],
+
+            (Preset::Vim, Event::SelectEducation | Event::SelectEmployment) => &["C-w w"],
+            (Preset::Vim, Event::ScrollDetailsDown) => &["C-e"],
+            (Preset::Vim, Event::ScrollDetailsUp) => &["C-y"],
+            (Preset::Vim, Event::ScrollAboutMeTextDown) => &["j", "C-e", "Down"],
+            (Preset::Vim, Event::ScrollAboutMeTextUp) => &["k", "C-y", "Up"],
+            (Preset::Vim, Event::ScrollPageDown) => &["C-f", "PageDown"],
+            (Preset::Vim, Event::ScrollPageUp) => &["C-b", "PageUp"],
+            (Preset::Vim, Event::OpenContactLink) => &["g x"],
+
+            (
+                Preset::Arrows,
+                Event::NextEmployer
+                | Event::NextEducation
+                | Event::NextContactField
+                | Event::ScrollAboutMeTextDown,
+            ) => &["Down"],
+            (
+                Preset::Arrows,
+                Event::PreviousEmployer
+                | Event::PreviousEducation
+                | Event::PreviousContactField
+                | Event::ScrollAboutMeTextUp,
+            ) => &["Up"],
+            (Preset::Arrows, Event::SelectEducation | Event::SelectEmployment) => {
+                &["Left", "Right"]
+            }
+            (Preset::Arrows, Event::ScrollDetailsDown) => &["C-Down"],
+            (Preset::Arrows, Event::ScrollDetailsUp) => &["C-Up"],
+            (Preset::Arrows, Event::FirstEntry) => &["Home"],
+            (Preset::Arrows, Event::LastEntry) => &["End"],
+            (Preset::Arrows, Event::OpenContactLink) => &["Enter"],
+
+            (_, Event::Quit) => &["q"],
+            (_, Event::SwitchToAboutMe) => &["a", "1"],
+            (_, Event::SwitchToContactInformation) => &["c", "2"],
+            (_, Event::SwitchToEmployment) => &["e", "3"],
+            (_, Event::NextTab) => &["Tab"],
+            (_, Event::PreviousTab) => &["S-Tab"],
+            (_, Event::Retry) => &["r"],
+            (_, Event::ShowHelp) => &["?"],
+            (_, Event::CloseHelp) => &["Esc", "?", "q"],
+            (Preset::Emacs, Event::ScrollHelpDown) => &["C-n", "Down"],
+            (Preset::Emacs, Event::ScrollHelpUp) => &["C-p", "Up"],
+            (_, Event::ScrollHelpDown) => &["j", "Down"],
+            (_, Event::ScrollHelpUp) => &["k", "Up"],
+            (_, Event::NextEmployer | Event::NextEducation | Event::NextContactField) => {
+                &["j", "Down"]
+            }
+            (
+                _,
+                Event::PreviousEmployer | Event::PreviousEducation | Event::PreviousContactField,
+            ) => &["k", "Up"],
+            (_, Event::SelectEducation | Event::SelectEmployment) => &["C-o"],
+            (_, Event::ScrollDetailsDown) => &["C-n"],
+            (_, Event::ScrollDetailsUp) => &["C-p"],
+            (_, Event::ScrollAboutMeTextDown) => &["C-n", "Down"],
+            (_, Event::ScrollAboutMeTextUp) => &["C-p", "Up"],
+            (_, Event::FirstEntry) => &["Home", "g g"],
+            (_, Event::LastEntry) => &["End", "G"],
+            (_, Event::ScrollPageDown) => &["PageDown"],
+            (_, Event::ScrollPageUp) => &["PageUp"],
+            (_, Event::CopyContactField) => &["y"],
+            (_, Event::OpenContactLink) => &["o"],
+        }
+    }
+}
+
+struct Binding {
+    event: Event,
+    context: Context,
+    keys: Vec<KeyEvent>,
+    notation: String,
+}
+
+/// What the keys pressed so far add up to.
+#[derive(Debug, PartialEq)]
+pub enum Resolved {
+    Event(Event),
+    /// The start of a longer sequence, so wait for the next key.
+    Pending,
+    Unbound,
+}
+
+/// The keys for every event in every context it applies to: the preset's,
+/// unless the user rebound them in the `[keybindings]` config table. Both
+/// dispatch and the footer read from here, so they cannot disagree.
+pub struct KeyBindings {
+    bindings: Vec<Binding>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).expect("default key bindings parse")
+    }
+}
+
+impl KeyBindings {
+    /// Builds the bindings from the `[keybindings]` table: an optional
+    /// `preset`, then `event = ["key", ...]` for every context the event
+    /// applies to, or `context.event = [...]` for just one. The keys given
+    /// replace the preset's, so an empty list, or empty key, unbinds.
+    pub fn new(config: &BTreeMap<String, Vec<String>>) -> Result<Self, String> {
+        let preset = match config.get("preset").map(Vec::as_slice) {
+            Some([preset]) => preset.parse()?,
+            Some(_) => return Err(String::from("preset in [keybindings] must be one name")),
+            None => Preset::Default,
+        };
+        for name in config.keys().filter(|name| *name != "preset") {
+            let (context, event) = match name.split_once('.') {
+                Some((context, event)) => (Some(context), event),
+                None => (None, name.as_str()),
+            };
+            let event = Event::from_name(event)
+                .ok_or_else(|| format!("unknown event {:?} in [keybindings]", name))?;
+            if let Some(context) = context {
+                match Context::from_name(context) {
+                    Some(context) if event.contexts().contains(&context) => {}
+                    Some(_) => {
+                        return Err(format!(
+                            "{} has no binding in [keybindings.{}]",
+                            event.name(),
+                            context
+                        ))
+                    }
+                    None => return Err(format!("unknown context {:?} in [keybindings]", context)),
+                }
+            }
+        }
+
+        let mut bindings = Vec::new();
+        for event in Event::ALL {
+            for &context in event.contexts() {
+                let notations = match config
+                    .get(&format!("{}.{}", context.name(), event.name()))
+                    .or_else(|| config.get(event.name()))
+                {
+                    Some(notations) => notations
+                        .iter()
+                        .map(String::as_str)
+                        .filter(|notation| !notation.trim().is_empty())
+                        .collect(),
+                    None => preset.keys(event).to_vec(),
+                };
+                for notation in notations {
+                    bindings.push(Binding {
+                        event,
+                        context,
+                        keys: parse_chord(notation)?,
+                        notation: notation.to_string(),
+                    });
+                }
+            }
+        }
+
+        let bindings = Self { bindings };
+        bindings.check_conflicts()?;
+        Ok(bindings)
+    }
+
+    /// Rejects two events sharing keys where both apply, including one
+    /// whose keys start another's, which could then never be reached.
+    fn check_conflicts(&self) -> Result<(), String> {
+        for (i, a) in self.bindings.iter().enumerate() {
+            for b in &self.bindings[i + 1..] {
+                let overlap = a.context == b.context
+                    || (a.context == Context::Global && b.context.includes_global())
+                    || (b.context == Context::Global && a.context.includes_global());
+                let shared = starts_with(&a.keys, &b.keys) || starts_with(&b.keys, &a.keys);
+                if a.event != b.event && overlap && shared {
+                    let context = match a.context {
+                        Context::Global => b.context,
+                        context => context,
+                    };
+                    return Err(format!(
+                        "{} ({:?}) and {} ({:?}) clash on the {} tab in [keybindings]",
+                        a.event.name(),
+                        a.notation,
+                        b.event.name(),
+                        b.notation,
+                        context.name()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn active(&self, context: Context) -> impl Iterator<Item = &Binding> {
+        self.bindings.iter().filter(move |binding| {
+            binding.context == context
+                || (binding.context == Context::Global && context.includes_global())
+        })
+    }
+
+    /// What `keys`, pressed in order, trigger in `context`.
+    pub fn resolve(&self, context: Context, keys: &[KeyEvent]) -> Resolved {
+        let mut pending = false;
+        for binding in self.active(context) {
+            if starts_with(&binding.keys, keys) {
+                if binding.keys.len() == keys.len() {
+                    return Resolved::Event(binding.event);
+                }
+                pending = true;
+            }
+        }
+        match pending {
+            true => Resolved::Pending,
+            false => Resolved::Unbound,
+        }
+    }
+
+    /// The events available in `context`, global ones first, each with the
+    /// keys bound to it in the order they were given.
+    pub fn bindings_for(&self, context: Context) -> Vec<(Event, Vec<&str>)> {
+        list_by_event(self.active(context))
+    }
+
+    /// The events bound in `context` itself, leaving out global ones.
+    pub fn bindings_in(&self, context: Context) -> Vec<(Event, Vec<&str>)> {
+        list_by_event(
+            self.bindings
+                .iter()
+                .filter(|binding| binding.context == context),
+        )
+    }
+}
+
+fn list_by_event<'a>(bindings: impl Iterator<Item = &'a Binding>) -> Vec<(Event, Vec<&'a str>)> {
+    let mut listed: Vec<(Event, Vec<&str>)> = Vec::new();
+    for binding in bindings {
+        match listed.iter_mut().find(|(event, _)| *event == binding.event) {
+            Some((_, keys)) => keys.push(&binding.notation),
+            None => listed.push((binding.event, vec![&binding.notation])),
+        }
+    }
+    listed
+}
+
+/// Which bindings apply to what is on screen.
+pub fn context(state: &State) -> Context {
+    if state.help_open {
+        return Context::Help;
+    }
+    match state.selected_tab {
+        TabsHeadings::AboutMe => Context::AboutMe,
+        TabsHeadings::ContactDetails => Context::Contact,
+        TabsHeadings::EmploymentAndEducation => match state.employment_or_education {
+            EmploymentEducation::Employment => Context::Employment,
+            EmploymentEducation::Education => Context::Education,
         },
     }
+}
 
-    match (key.code, key.modifiers) {
-        (event::KeyCode::Char('o'), event::KeyModifiers::CONTROL) => {
-            state.toggle_employment_or_education()
+pub fn global_key_map(key: KeyEvent, state: &mut State) {
+    state.status_message = None;
+    state.pending_keys.push(key);
+
+    match state
+        .key_bindings
+        .resolve(context(state), &state.pending_keys)
+    {
+        Resolved::Event(event) => {
+            state.pending_keys.clear();
+            handle_event(event, state);
+        }
+        Resolved::Pending => {}
+        Resolved::Unbound => {
+            // A sequence that went nowhere: start over from the latest key.
+            let restart = state.pending_keys.len() > 1;
+            state.pending_keys.clear();
+            if restart {
+                global_key_map(key, state);
+            }
         }
-        _ => {}
     }
 }
 
-fn about_me_keymap(key: KeyEvent, state: &mut State) {
-    match (key.code, key.modifiers) {
-        (event::KeyCode::Char('n'), event::KeyModifiers::CONTROL) => {
-            state.scroll_about_me_down();
+/// Clicks pick tabs and list entries, and the wheel scrolls whatever it is
+/// over, laying out the screen as it was last drawn.
+pub fn mouse_map(mouse: MouseEvent, state: &mut State) {
+    let (column, row) = (mouse.column, mouse.row);
+    let within = |rect: Rect| {
+        (rect.left()..rect.right()).contains(&column) && (rect.top()..rect.bottom()).contains(&row)
+    };
+    let chunks = ui::app_layout(state.screen);
+
+    if state.help_open {
+        if let MouseEventKind::ScrollDown | MouseEventKind::ScrollUp = mouse.kind {
+            let max_help_scroll = help::max_scroll(state);
+            for _ in 0..WHEEL_LINES {
+                match mouse.kind == MouseEventKind::ScrollDown {
+                    true => state.scroll_help_down(max_help_scroll),
+                    false => state.scroll_help_up(),
+                }
+            }
         }
-        (event::KeyCode::Char('p'), event::KeyModifiers::CONTROL) => {
-            state.scroll_about_me_up();
+        return;
+    }
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            state.status_message = None;
+            state.pending_keys.clear();
+            if within(chunks[1]) {
+                // The title after the last tab is the way out.
+                match tabs::tab_at(chunks[1], column, &state.key_bindings)
+                    .map(|i| TabsHeadings::ALL.get(i))
+                {
+                    Some(Some(&tab)) => state.set_selected_tab(tab),
+                    Some(None) => state.quit(),
+                    None => {}
+                }
+            } else if state.selected_tab == TabsHeadings::EmploymentAndEducation {
+                let layout = employment::layout(chunks[2]);
+                if within(layout.employment) {
+                    let heights = employment::employment_heights(state);
+                    let selected = state.selected_employment_entry;
+                    if let Some(i) =
+                        employment::entry_at(layout.employment, &heights, selected, row)
+                    {
+                        state.select_employment_entry(i);
+                    }
+                } else if within(layout.education) {
+                    let heights = employment::education_heights(state);
+                    let selected = state.selected_education_entry;
+                    if let Some(i) = employment::entry_at(layout.education, &heights, selected, row)
+                    {
+                        state.select_education_entry(i);
+                    }
+                }
+            }
+        }
+        MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+            let down = mouse.kind == MouseEventKind::ScrollDown;
+            match state.selected_tab {
+                TabsHeadings::AboutMe if within(about_me::layout(chunks[2])[1]) => {
+                    let max_scroll = about_me::max_scroll(state);
+                    for _ in 0..WHEEL_LINES {
+                        match down {
+                            true => state.scroll_about_me_down(max_scroll),
+                            false => state.scroll_about_me_up(),
+                        }
+                    }
+                }
+                TabsHeadings::EmploymentAndEducation => {
+                    let layout = employment::layout(chunks[2]);
+                    if within(layout.details) {
+                        let max_scroll = employment::max_details_scroll(state);
+                        for _ in 0..WHEEL_LINES {
+                            match down {
+                                true => state.scroll_details_down(max_scroll),
+                                false => state.scroll_details_up(),
+                            }
+                        }
+                    } else if within(layout.employment) {
+                        state.select_employment_entry(state.selected_employment_entry);
+                        match down {
+                            true => state.next_employer(),
+                            false => state.previous_employer(),
+                        }
+                    } else if within(layout.education) {
+                        state.select_education_entry(state.selected_education_entry);
+                        match down {
+                            true => state.next_educator(),
+                            false => state.previous_educator(),
+                        }
+                    }
+                }
+                _ => {}
+            }
         }
         _ => {}
     }
 }
+
+/// How many lines a page is in the pane `ScrollPageDown` scrolls: the help
+/// screen, the About Me text, or the details beside the lists.
+fn page_height(state: &State) -> u16 {
+    let chunk = ui::app_layout(state.screen)[2];
+    match context(state) {
+        Context::Help => help::page_height(state),
+        // The About Me text has no border.
+        Context::AboutMe => about_me::layout(chunk)[1].height.max(1),
+        _ => {
+            let details = employment::layout(chunk).details;
+            Block::default()
+                .borders(Borders::ALL)
+                .inner(details)
+                .height
+                .max(1)
+        }
+    }
+}
+
+fn scroll_page(state: &mut State, down: bool) {
+    let context = context(state);
+    let max_scroll = match context {
+        Context::Help => help::max_scroll(state),
+        Context::AboutMe => about_me::max_scroll(state),
+        _ => employment::max_details_scroll(state),
+    };
+    for _ in 0..page_height(state) {
+        match (context, down) {
+            (Context::Help, true) => state.scroll_help_down(max_scroll),
+            (Context::Help, false) => state.scroll_help_up(),
+            (Context::AboutMe, true) => state.scroll_about_me_down(max_scroll),
+            (Context::AboutMe, false) => state.scroll_about_me_up(),
+            (_, true) => state.scroll_details_down(max_scroll),
+            (_, false) => state.scroll_details_up(),
+        }
+    }
+}
+
+fn first_entry(state: &mut State) {
+    match context(state) {
+        Context::Contact => state.select_contact_field(0),
+        Context::Employment => state.select_employment_entry(0),
+        Context::Education => state.select_education_entry(0),
+        Context::Help => state.help_scroll_offset = 0,
+        Context::Global | Context::AboutMe => {}
+    }
+}
+
+fn last_entry(state: &mut State) {
+    match context(state) {
+        Context::Contact => {
+            let last = state.resume.contact.fields().len().saturating_sub(1);
+            state.select_contact_field(last);
+        }
+        Context::Employment => {
+            state.select_employment_entry(state.resume.employment.len().saturating_sub(1))
+        }
+        Context::Education => {
+            state.select_education_entry(state.resume.education.len().saturating_sub(1))
+        }
+        Context::Help => state.help_scroll_offset = help::max_scroll(state),
+        Context::Global | Context::AboutMe => {}
+    }
+}
+
+pub fn handle_event(event: Event, state: &mut State) {
+    match event {
+        Event::Quit => state.quit(),
+        Event::SwitchToAboutMe => state.set_selected_tab(TabsHeadings::AboutMe),
+        Event::SwitchToContactInformation => state.set_selected_tab(TabsHeadings::ContactDetails),
+        Event::SwitchToEmployment => state.set_selected_tab(TabsHeadings::EmploymentAndEducation),
+        Event::NextTab => state.next_tab(),
+        Event::PreviousTab => state.previous_tab(),
+        Event::Retry => state.retry_failed_loads(),
+        Event::NextEmployer => state.next_employer(),
+        Event::PreviousEmployer => state.previous_employer(),
+        Event::NextEducation => state.next_educator(),
+        Event::PreviousEducation => state.previous_educator(),
+        Event::SelectEducation | Event::SelectEmployment => state.toggle_employment_or_education(),
+        Event::ScrollDetailsDown => {
+            state.scroll_details_down(employment::max_details_scroll(state))
+        }
+        Event::ScrollDetailsUp => state.scroll_details_up(),
+        Event::NextContactField => state.next_contact_field(),
+        Event::PreviousContactField => state.previous_contact_field(),
+        Event::CopyContactField => state.copy_selected_contact_field(),
+        Event::OpenContactLink => state.open_selected_contact_field(),
+        Event::ScrollAboutMeTextDown => state.scroll_about_me_down(about_me::max_scroll(state)),
+        Event::ScrollAboutMeTextUp => state.scroll_about_me_up(),
+        Event::FirstEntry => first_entry(state),
+        Event::LastEntry => last_entry(state),
+        Event::ScrollPageDown => scroll_page(state, true),
+        Event::ScrollPageUp => scroll_page(state, false),
+        Event::ShowHelp => state.show_help(),
+        Event::CloseHelp => state.close_help(),
+        Event::ScrollHelpDown => state.scroll_help_down(help::max_scroll(state)),
+        Event::ScrollHelpUp => state.scroll_help_up(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn bindings(pairs: &[(&str, &str)]) -> Result<KeyBindings, String> {
+        let config = pairs
+            .iter()
+            .map(|(name, key)| (name.to_string(), vec![key.to_string()]))
+            .collect();
+        KeyBindings::new(&config)
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(
+            parse_key("q"),
+            Ok(key(KeyCode::Char('q'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("C-o"),
+            Ok(key(KeyCode::Char('o'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            parse_key("S-Tab"),
+            Ok(key(KeyCode::BackTab, KeyModifiers::SHIFT))
+        );
+        assert_eq!(
+            parse_key("C-M-PageDown"),
+            Ok(key(
+                KeyCode::PageDown,
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            ))
+        );
+        assert_eq!(
+            parse_key("-"),
+            Ok(key(KeyCode::Char('-'), KeyModifiers::NONE))
+        );
+        assert_eq!(parse_key("F5"), Ok(key(KeyCode::F(5), KeyModifiers::NONE)));
+        assert!(parse_key("").is_err());
+        assert!(parse_key("C-").is_err());
+        assert!(parse_key("xyz").is_err());
+        assert!(parse_key("F13").is_err());
+
+        assert_eq!(
+            parse_chord("g  g"),
+            Ok(vec![
+                key(KeyCode::Char('g'), KeyModifiers::NONE),
+                key(KeyCode::Char('g'), KeyModifiers::NONE),
+            ])
+        );
+        assert!(parse_chord(" ").is_err());
+    }
+
+    #[test]
+    fn test_key_bindings_apply_overrides() {
+        let bindings = bindings(&[
+            ("next_employer", "n"),
+            ("education.scroll_details_down", "J"),
+        ])
+        .unwrap();
+
+        let n = [key(KeyCode::Char('n'), KeyModifiers::NONE)];
+        let j = [key(KeyCode::Char('j'), KeyModifiers::NONE)];
+        let shift_j = [key(KeyCode::Char('J'), KeyModifiers::SHIFT)];
+        let ctrl_n = [key(KeyCode::Char('n'), KeyModifiers::CONTROL)];
+        assert_eq!(
+            bindings.resolve(Context::Employment, &n),
+            Resolved::Event(Event::NextEmployer)
+        );
+        assert_eq!(bindings.resolve(Context::Employment, &j), Resolved::Unbound);
+        assert_eq!(
+            bindings.resolve(Context::Education, &j),
+            Resolved::Event(Event::NextEducation)
+        );
+        assert_eq!(
+            bindings.resolve(Context::Education, &shift_j),
+            Resolved::Event(Event::ScrollDetailsDown)
+        );
+        assert_eq!(
+            bindings.resolve(Context::Employment, &ctrl_n),
+            Resolved::Event(Event::ScrollDetailsDown)
+        );
+        assert!(bindings
+            .bindings_for(Context::Employment)
+            .contains(&(Event::NextEmployer, vec!["n"])));
+    }
+
+    #[test]
+    fn test_key_bindings_take_several_keys() {
+        let config = BTreeMap::from([
+            (
+                String::from("quit"),
+                vec![String::from("q"), String::from("C-c")],
+            ),
+            (String::from("show_help"), vec![]),
+        ]);
+        let bindings = KeyBindings::new(&config).unwrap();
+
+        for keys in [
+            [key(KeyCode::Char('q'), KeyModifiers::NONE)],
+            [key(KeyCode::Char('c'), KeyModifiers::CONTROL)],
+        ] {
+            assert_eq!(
+                bindings.resolve(Context::AboutMe, &keys),
+                Resolved::Event(Event::Quit)
+            );
+        }
+        assert!(bindings
+            .bindings_for(Context::Global)
+            .contains(&(Event::Quit, vec!["q", "C-c"])));
+        assert!(!bindings
+            .bindings_for(Context::Global)
+            .iter()
+            .any(|(event, _)| *event == Event::ShowHelp));
+
+        let preset = BTreeMap::from([(
+            String::from("preset"),
+            vec![String::from("vim"), String::from("emacs")],
+        )]);
+        assert!(KeyBindings::new(&preset).is_err());
+    }
+
+    #[test]
+    fn test_key_bindings_reject_bad_config() {
+        assert!(bindings(&[("launch_rockets", "x")]).is_err());
+        assert!(bindings(&[("contact.next_employer", "x")]).is_err());
+        assert!(bindings(&[("sidebar.quit", "x")]).is_err());
+        assert!(bindings(&[("preset", "nano")]).is_err());
+        // `e` already switches to the employment tab, from everywhere.
+        assert!(bindings(&[("next_contact_field", "e")]).is_err());
+        // `g` would fire before `g x` could be finished.
+        assert!(bindings(&[("preset", "vim"), ("copy_contact_field", "g")]).is_err());
+        // Different contexts may share keys.
+        assert!(bindings(&[("next_contact_field", "C-o")]).is_ok());
+    }
+
+    #[test]
+    fn test_presets_are_consistent() {
+        for preset in ["default", "vim", "emacs", "arrows"] {
+            let bindings = bindings(&[("preset", preset)]).unwrap();
+            for binding in &bindings.bindings {
+                assert_eq!(
+                    bindings.resolve(binding.context, &binding.keys),
+                    Resolved::Event(binding.event)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sequences_dispatch() {
+        let mut state = State::default();
+        state.key_bindings = bindings(&[("preset", "emacs")]).unwrap();
+        let ctrl = |c| key(KeyCode::Char(c), KeyModifiers::CONTROL);
+
+        global_key_map(ctrl('c'), &mut state);
+        global_key_map(key(KeyCode::Char('e'), KeyModifiers::NONE), &mut state);
+        assert_eq!(state.selected_tab, TabsHeadings::EmploymentAndEducation);
+
+        // A dead end starts over, so C-x C-x C-c still quits.
+        global_key_map(ctrl('x'), &mut state);
+        global_key_map(ctrl('x'), &mut state);
+        assert!(!state.should_quit);
+        global_key_map(ctrl('c'), &mut state);
+        assert!(state.should_quit);
+    }
+
+    /// An entry whose details are too long for any pane to show at once.
+    fn long_employment() -> crate::employment::Employment {
+        use crate::employment::{Details, Employment};
+
+        Employment {
+            details: Details {
+                achievements: vec![String::from("Shipped it"); 100],
+                ..Details::default()
+            },
+            ..Employment::_default()
+        }
+    }
+
+    #[test]
+    fn test_standard_navigation() {
+        let mut state = State::default();
+        state.resume.employment = vec![long_employment(); 3];
+        state.screen = Rect::new(0, 0, 120, 50);
+        let press = |code, state: &mut State| global_key_map(key(code, KeyModifiers::NONE), state);
+
+        press(KeyCode::Char('3'), &mut state);
+        assert_eq!(state.selected_tab, TabsHeadings::EmploymentAndEducation);
+        press(KeyCode::End, &mut state);
+        assert_eq!(state.selected_employment_entry, 2);
+        press(KeyCode::Up, &mut state);
+        assert_eq!(state.selected_employment_entry, 1);
+        press(KeyCode::Char('g'), &mut state);
+        press(KeyCode::Char('g'), &mut state);
+        assert_eq!(state.selected_employment_entry, 0);
+
+        // A page is the height of the details pane, less its border.
+        let details = employment::layout(ui::app_layout(state.screen)[2]).details;
+        press(KeyCode::PageDown, &mut state);
+        assert_eq!(state.details_scroll_offset, details.height - 2);
+        press(KeyCode::PageUp, &mut state);
+        assert_eq!(state.details_scroll_offset, 0);
+
+        press(KeyCode::Tab, &mut state);
+        assert_eq!(state.selected_tab, TabsHeadings::AboutMe);
+        global_key_map(key(KeyCode::BackTab, KeyModifiers::SHIFT), &mut state);
+        assert_eq!(state.selected_tab, TabsHeadings::EmploymentAndEducation);
+    }
+
+    #[test]
+    fn test_page_down_stops_at_the_bottom() {
+        let mut state = State::default();
+        state.screen = Rect::new(0, 0, 80, 40);
+        state.resume.about.about_me = vec!["A line of text about me."; 40].join("\n");
+        state.resume.employment = vec![long_employment()];
+        let press = |code, state: &mut State| global_key_map(key(code, KeyModifiers::NONE), state);
+
+        let bottom = about_me::max_scroll(&state);
+        assert!(bottom > 0);
+        for _ in 0..10 {
+            press(KeyCode::PageDown, &mut state);
+        }
+        assert_eq!(state.about_me_scroll_offset, bottom);
+        press(KeyCode::Down, &mut state);
+        assert_eq!(state.about_me_scroll_offset, bottom);
+
+        press(KeyCode::Char('3'), &mut state);
+        let bottom = employment::max_details_scroll(&state);
+        assert!(bottom > 0);
+        for _ in 0..10 {
+            press(KeyCode::PageDown, &mut state);
+        }
+        assert_eq!(state.details_scroll_offset, bottom);
+
+        // Details that fit don't scroll at all.
+        state.resume.employment = vec![crate::employment::Employment::_default()];
+        state.details_scroll_offset = 0;
+        press(KeyCode::PageDown, &mut state);
+        assert_eq!(state.details_scroll_offset, 0);
+    }
+
+    #[test]
+    fn test_help_is_modal() {
+        let mut state = State::default();
+        state.screen = Rect::new(0, 0, 80, 20);
+        let press = |code, state: &mut State| global_key_map(key(code, KeyModifiers::NONE), state);
+
+        global_key_map(key(KeyCode::Char('?'), KeyModifiers::SHIFT), &mut state);
+        assert!(state.help_open);
+
+        // Global keys wait until the help screen is closed.
+        press(KeyCode::Tab, &mut state);
+        assert_eq!(state.selected_tab, TabsHeadings::AboutMe);
+        press(KeyCode::Char('q'), &mut state);
+        assert!(!state.should_quit && !state.help_open);
+
+        press(KeyCode::Char('?'), &mut state);
+        press(KeyCode::End, &mut state);
+        let bottom = help::max_scroll(&state);
+        assert!(bottom > 0);
+        assert_eq!(state.help_scroll_offset, bottom);
+        press(KeyCode::Down, &mut state);
+        assert_eq!(state.help_scroll_offset, bottom);
+        press(KeyCode::Up, &mut state);
+        assert_eq!(state.help_scroll_offset, bottom - 1);
+
+        press(KeyCode::Esc, &mut state);
+        assert!(!state.help_open);
+        press(KeyCode::Tab, &mut state);
+        assert_eq!(state.selected_tab, TabsHeadings::ContactDetails);
+    }
+
+    #[test]
+    fn test_mouse() {
+        let mut state = State::default();
+        state.resume.employment = vec![long_employment(); 2];
+        let area = Rect::new(0, 0, 120, 50);
+        state.screen = area;
+        let click = |column, row| MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+
+        // The tab bar sits below the banner: " About Me (a) | Contact ..."
+        let tabs = ui::app_layout(area)[1];
+        mouse_map(click(tabs.x + 20, tabs.y), &mut state);
+        assert_eq!(state.selected_tab, TabsHeadings::ContactDetails);
+
+        mouse_map(click(tabs.x + 50, tabs.y), &mut state);
+        assert_eq!(state.selected_tab, TabsHeadings::EmploymentAndEducation);
+
+        // Entries are four lines tall, inside the list's border.
+        let list = employment::layout(ui::app_layout(area)[2]).employment;
+        mouse_map(click(list.x + 2, list.y + 5), &mut state);
+        assert_eq!(state.selected_employment_entry, 1);
+
+        let details = employment::layout(ui::app_layout(area)[2]).details;
+        let wheel = MouseEvent {
+            kind: MouseEventKind::ScrollDown,
+            ..click(details.x + 5, details.y + 5)
+        };
+        mouse_map(wheel, &mut state);
+        assert_eq!(state.details_scroll_offset, WHEEL_LINES);
+
+        mouse_map(click(tabs.x + 75, tabs.y), &mut state);
+        assert!(state.should_quit);
+    }
+}
@@ -0,0 +1,321 @@
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::{
+    about_me::AboutMe,
+    cache::Cache,
+    contact::ContactDetails,
+    employment::{Education, Employment},
+    error::LoadError,
+    resume::Profile,
+    source::{Fetched, ResumeSource, Section},
+};
+
+pub enum LoadEvent {
+    Employment(Result<Vec<Employment>, LoadError>),
+    Education(Result<Vec<Education>, LoadError>),
+    Contact(Result<ContactDetails, LoadError>),
+    AboutMe(Result<AboutMe, LoadError>),
+    Profile(Result<Profile, LoadError>),
+}
+
+impl LoadEvent {
+    pub fn section(&self) -> Section {
+        match self {
+            LoadEvent::Employment(_) => Section::Employment,
+            LoadEvent::Education(_) => Section::Education,
+            LoadEvent::Contact(_) => Section::Contact,
+            LoadEvent::AboutMe(_) => Section::AboutMe,
+            LoadEvent::Profile(_) => Section::Profile,
+        }
+    }
+}
+
+pub fn parse_json<T: DeserializeOwned>(section: Section, bytes: &[u8]) -> Result<T, LoadError> {
+    serde_json::from_slice(bytes).map_err(|err| LoadError::malformed_json(section.key(), err))
+}
+
+fn section_event(section: Section, bytes: Result<&[u8], LoadError>) -> LoadEvent {
+    match section {
+        Section::Employment => {
+            LoadEvent::Employment(bytes.and_then(|bytes| parse_json(section, bytes)))
+        }
+        Section::Education => {
+            LoadEvent::Education(bytes.and_then(|bytes| parse_json(section, bytes)))
+        }
+        Section::Contact => LoadEvent::Contact(bytes.and_then(|bytes| parse_json(section, bytes))),
+        Section::AboutMe => LoadEvent::AboutMe(bytes.and_then(|bytes| parse_json(section, bytes))),
+        Section::Profile => LoadEvent::Profile(bytes.and_then(|bytes| parse_json(section, bytes))),
+    }
+}
+
+/// Reports the cached copy of `section` straight away, if there is one, then
+/// revalidates it against the source and reports again only if it changed.
+async fn load_section(
+    source: &dyn ResumeSource,
+    cache: Option<&Cache>,
+    section: Section,
+    tx: &UnboundedSender<LoadEvent>,
+) {
+    let key = section.key();
+    let cache = cache.zip(source.cache_namespace());
+
+    let cached = cache.as_ref().and_then(|(cache, ns)| cache.read(ns, key));
+    if let Some(cached) = &cached {
+        let _ = tx.send(section_event(section, Ok(&cached.bytes)));
+    }
+
+    let etag = cached.as_ref().and_then(|cached| cached.etag.as_deref());
+    match source.fetch_if_changed(key, etag).await {
+        Ok(Fetched::Modified { bytes, etag, .. }) => {
+            if let Some((cache, ns)) = &cache {
+                let _ = cache.write(ns, key, &bytes, etag.as_deref());
+            }
+            let _ = tx.send(section_event(section, Ok(&bytes)));
+        }
+        Ok(Fetched::NotModified) => {}
+        // Stale data beats no data when the source can't be reached.
+        Err(_) if cached.is_some() => {}
+        Err(err) if err.is_not_found() && section.is_optional() => {
+            let _ = tx.send(section_event(section, Ok(b"{}")));
+        }
+        Err(err) => {
+            let _ = tx.send(section_event(section, Err(err)));
+        }
+    }
+}
+
+/// Loads sections of the resume in the background, reporting each one as
+/// soon as it is ready.
+pub struct Loader {
+    source: Arc<dyn ResumeSource>,
+    cache: Option<Cache>,
+    tx: UnboundedSender<LoadEvent>,
+    rx: UnboundedReceiver<LoadEvent>,
+}
+
+impl Loader {
+    pub fn new(source: Arc<dyn ResumeSource>) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            source,
+            cache: None,
+            tx,
+            rx,
+        }
+    }
+
+    pub fn with_cache(mut self, cache: Option<Cache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn load(&self, sections: &[Section]) {
+        for section in sections.iter().copied() {
+            let source = self.source.clone();
+            let cache = self.cache.clone();
+            let tx = self.tx.clone();
+            tokio::spawn(async move {
+                load_section(source.as_ref(), cache.as_ref(), section, &tx).await;
+            });
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Option<LoadEvent> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Waits for every load that has been started to finish, including any
+    /// revalidation of cached copies, and returns everything reported.
+    pub async fn finish(self) -> Vec<LoadEvent> {
+        let Self { tx, mut rx, .. } = self;
+        drop(tx);
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{BundledSource, SampleSource};
+
+    #[tokio::test]
+    async fn test_loader_reports_every_section() {
+        let loader = Loader::new(Arc::new(SampleSource));
+        loader.load(&Section::ALL);
+
+        let events = loader.finish().await;
+        let sections: Vec<Section> = events.iter().map(LoadEvent::section).collect();
+        assert_eq!(sections.len(), 5);
+        assert!(sections.contains(&Section::Profile));
+        assert!(sections.contains(&Section::AboutMe));
+        assert!(sections.contains(&Section::Employment));
+        assert!(sections.contains(&Section::Education));
+        assert!(sections.contains(&Section::Contact));
+
+        for event in events {
+            match event {
+                LoadEvent::Profile(Ok(profile)) => {
+                    assert_eq!(profile.name.as_deref(), Some("Ada Lovelace"))
+                }
+                LoadEvent::Employment(Ok(employment)) => {
+                    assert_eq!(employment[0].employer, "Analytical Engine")
+                }
+                LoadEvent::Education(Ok(education)) => {
+                    assert_eq!(education[0].qualification, "Mathematics")
+                }
+                LoadEvent::Contact(Ok(contact)) => {
+                    assert_eq!(contact.email.as_deref(), Some("ada@example.com"))
+                }
+                LoadEvent::AboutMe(Ok(about)) => assert!(about.about_me.contains("Engine")),
+                _ => panic!("unexpected load event"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unbundled_sections_fail_loudly() {
+        let loader = Loader::new(Arc::new(BundledSource));
+        loader.load(&[Section::Employment]);
+
+        match &loader.finish().await[..] {
+            [LoadEvent::Employment(Err(err))] => {
+                assert!(err.to_string().contains("not bundled"))
+            }
+            _ => panic!("expected employment to fail"),
+        }
+    }
+
+    struct BrokenSource;
+
+    #[async_trait::async_trait]
+    impl ResumeSource for BrokenSource {
+        async fn fetch(&self, _key: &str) -> Result<Vec<u8>, LoadError> {
+            Ok(b"[{\"employer\": }]".to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_loader_reports_malformed_json() {
+        let mut loader = Loader::new(Arc::new(BrokenSource));
+        loader.load(&[Section::Employment]);
+
+        match loader.rx.recv().await.unwrap() {
+            LoadEvent::Employment(Err(LoadError::MalformedJson { line, column, .. })) => {
+                assert_eq!((line, column), (1, 15));
+            }
+            _ => panic!("expected a malformed JSON error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_optional_section_is_empty() {
+        let source =
+            crate::source::LocalSource::new(std::path::PathBuf::from("/definitely/not/here"));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        load_section(&source, None, Section::Profile, &tx).await;
+        match rx.try_recv().unwrap() {
+            LoadEvent::Profile(Ok(profile)) => assert!(profile.name.is_none()),
+            _ => panic!("expected an empty profile"),
+        }
+
+        load_section(&source, None, Section::AboutMe, &tx).await;
+        match rx.try_recv().unwrap() {
+            LoadEvent::AboutMe(Ok(about)) => assert!(about.about_me.is_empty()),
+            _ => panic!("expected an empty about me"),
+        }
+
+        load_section(&source, None, Section::Contact, &tx).await;
+        assert!(matches!(rx.try_recv().unwrap(), LoadEvent::Contact(Ok(_))));
+
+        load_section(&source, None, Section::Employment, &tx).await;
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            LoadEvent::Employment(Err(_))
+        ));
+    }
+
+    struct RevalidatingSource {
+        etag: &'static str,
+        body: &'static [u8],
+        requests: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ResumeSource for RevalidatingSource {
+        async fn fetch(&self, _key: &str) -> Result<Vec<u8>, LoadError> {
+            Ok(self.body.to_vec())
+        }
+
+        async fn fetch_if_changed(
+            &self,
+            _key: &str,
+            etag: Option<&str>,
+        ) -> Result<Fetched, LoadError> {
+            self.requests.lock().unwrap().push(etag.map(String::from));
+            match etag == Some(self.etag) {
+                true => Ok(Fetched::NotModified),
+                false => Ok(Fetched::Modified {
+                    bytes: self.body.to_vec(),
+                    etag: Some(self.etag.to_string()),
+                    modified: None,
+                }),
+            }
+        }
+
+        fn cache_namespace(&self) -> Option<String> {
+            Some(String::from("bucket"))
+        }
+    }
+
+    fn employment_len(event: LoadEvent) -> usize {
+        match event {
+            LoadEvent::Employment(Ok(employment)) => employment.len(),
+            _ => panic!("expected employment"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_loader_serves_cache_then_revalidates() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+        cache
+            .write("bucket", "employment.json", b"[]", Some("\"old\""))
+            .unwrap();
+
+        let source = Arc::new(RevalidatingSource {
+            etag: "\"new\"",
+            body: br#"[{"employer": "e", "role": "r", "start_date": "s", "end_date": "e"}]"#,
+            requests: Default::default(),
+        });
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        load_section(source.as_ref(), Some(&cache), Section::Employment, &tx).await;
+        assert_eq!(employment_len(rx.try_recv().unwrap()), 0);
+        assert_eq!(employment_len(rx.try_recv().unwrap()), 1);
+        assert_eq!(
+            cache
+                .read("bucket", "employment.json")
+                .unwrap()
+                .etag
+                .as_deref(),
+            Some("\"new\"")
+        );
+
+        load_section(source.as_ref(), Some(&cache), Section::Employment, &tx).await;
+        assert_eq!(employment_len(rx.try_recv().unwrap()), 1);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(
+            *source.requests.lock().unwrap(),
+            vec![Some(String::from("\"old\"")), Some(String::from("\"new\""))]
+        );
+    }
+}
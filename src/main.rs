@@ -1,9 +1,11 @@
+use clap::Parser;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use std::{error::Error, io};
+use std::{env, error::Error, io, path::PathBuf, process, thread, time::Duration};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use tui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout},
@@ -11,37 +13,157 @@ use tui::{
 };
 
 use crate::{
-    keymap::global_key_map,
-    settings::Settings,
-    state::State,
+    cache::Cache,
+    cli::{Cli, Command},
+    config::Config,
+    keymap::{global_key_map, mouse_map, KeyBindings},
+    loader::Loader,
+    settings::{DataSource, Settings},
+    source::Section,
+    state::{Effect, State},
     ui::{
         about_me::render_about_me,
         banner::render_banner,
         contact_details::render_contact_details,
         employment::render_employment,
+        error::render_load_errors,
+        help::render_help,
         keymap::render_keymap,
         loading::render_loading_screen,
         tabs::{render_tabs, TabsHeadings},
     },
 };
 
+mod about_me;
+mod api;
 mod assets;
+mod cache;
+mod cli;
+mod commands;
+mod config;
+mod contact;
+mod effects;
 mod employment;
+mod error;
 mod events;
+mod export;
 mod keymap;
+mod loader;
+mod resume;
+mod session;
 mod settings;
+mod source;
 mod state;
 mod ui;
+mod watcher;
+
+fn load_settings(cli: &Cli) -> Result<Settings, String> {
+    let file = match cli
+        .config
+        .clone()
+        .or_else(|| env::var_os("RESUME_CONFIG").map(PathBuf::from))
+    {
+        Some(path) => Config::from_file(&path, true)?,
+        None => match Config::default_path() {
+            Some(path) => Config::from_file(&path, false)?,
+            None => Config::default(),
+        },
+    };
+
+    Settings::from_layers(vec![file, Config::from_env()?, cli.config_layer()])
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli).await {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let settings = load_settings(&cli)?;
+    // Print the configuration even when its bindings are wrong, since that
+    // is when it is most needed.
+    if cli.print_config {
+        print!("{}", Config::from(&settings).to_toml()?);
+        return Ok(());
+    }
+    let key_bindings = KeyBindings::new(&settings.keybindings)?;
+
+    match cli.command.unwrap_or(Command::Tui) {
+        Command::Tui => run_tui(settings, key_bindings).await,
+        Command::Export {
+            format,
+            output,
+            options,
+        } => commands::export(&settings, format, &options, output.as_deref()).await,
+        Command::Validate => commands::validate(&settings).await,
+        Command::Serve {
+            addr,
+            ssh: true,
+            max_connections,
+            ssh_options,
+            ..
+        } => commands::serve_ssh(settings, addr, max_connections, &ssh_options).await,
+        Command::Serve {
+            addr,
+            telnet: true,
+            max_connections,
+            ..
+        } => commands::serve_telnet(settings, addr, max_connections).await,
+        Command::Serve {
+            addr,
+            web: true,
+            max_connections,
+            ..
+        } => commands::serve_web(settings, addr, max_connections).await,
+        Command::Serve {
+            addr, api: true, ..
+        } => commands::serve_api(&settings, addr).await,
+        Command::Serve { addr, .. } => commands::serve(&settings, addr).await,
+        Command::Fetch { output } => commands::fetch(&settings, &output).await,
+        Command::Print { section } => commands::print(&settings, section).await,
+    }
+}
+
+async fn run_tui(settings: Settings, key_bindings: KeyBindings) -> Result<(), Box<dyn Error>> {
+    // Keep the watcher alive for as long as the TUI runs.
+    let (_watcher, changes) = match (settings.watch, &settings.data_source) {
+        (false, _) => (None, None),
+        (true, DataSource::Local(dir)) => {
+            let (watcher, changes) = watcher::watch(dir)
+                .map_err(|err| format!("cannot watch {}: {}", dir.display(), err))?;
+            (Some(watcher), Some(changes))
+        }
+        (true, source) => {
+            return Err(format!(
+                "--watch needs a local data directory or document, not {}",
+                source
+            )
+            .into())
+        }
+    };
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let res = run_app(&mut terminal).await;
+    let (events_tx, mut events) = mpsc::unbounded_channel();
+    thread::spawn(move || forward_terminal_events(events_tx));
+
+    let res = run_app(
+        &mut terminal,
+        &settings,
+        key_bindings,
+        &mut events,
+        changes,
+        |effect| effects::perform(effect, &mut io::stdout()),
+    )
+    .await;
 
     disable_raw_mode()?;
     execute!(
@@ -51,38 +173,91 @@ async fn main() -> Result<(), Box<dyn Error>> {
     )?;
     terminal.show_cursor()?;
 
-    if let Err(err) = res {
-        println!("{:?}", err);
-    }
-
+    // Only now the terminal is restored can `main` report what went wrong.
+    res?;
     Ok(())
 }
 
-async fn run_app<B: tui::backend::Backend>(terminal: &mut Terminal<B>) -> io::Result<()> {
+/// Reads the local terminal on a thread of its own, since crossterm only
+/// offers a blocking read, until `run_app` hangs up.
+fn forward_terminal_events(events: UnboundedSender<event::Event>) {
+    while !events.is_closed() {
+        match event::poll(Duration::from_millis(100)) {
+            Ok(true) => match event::read() {
+                Ok(event) => {
+                    let _ = events.send(event);
+                }
+                Err(_) => return,
+            },
+            Ok(false) => {}
+            Err(_) => return,
+        }
+    }
+}
+
+/// Runs one viewer of the resume until they quit or `events` closes. The
+/// local terminal and every remote session go through here, each with its
+/// own `State`.
+async fn run_app<B: tui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    settings: &Settings,
+    key_bindings: KeyBindings,
+    events: &mut UnboundedReceiver<event::Event>,
+    mut changes: Option<UnboundedReceiver<Section>>,
+    mut perform: impl FnMut(Effect) -> String,
+) -> io::Result<()> {
     let mut state = State::default();
-    let settings = Settings::default();
-    // let _ = state.load_employment_from_file();
-    // let _ = state._load_education_from_file(&settings);
-    let _ = state.load_employment_file_from_s3(&settings).await;
-    let _ = state.load_education_file_from_s3(&settings).await;
-    let settings = Settings::default();
+    state.theme = settings.theme;
+    state.key_bindings = key_bindings;
+    state.set_selected_tab(settings.initial_tab);
+    let source = source::from_settings(settings).await;
+    let mut loader = Loader::new(source).with_cache(Cache::for_settings(settings));
+    state.start_loading(&Section::ALL);
     loop {
-        terminal.draw(|f| match state.is_loading {
-            true => {
-                draw_loading_screen(f, &mut state);
-            }
-            false => {
-                draw_app(f, &state);
+        loader.load(&state.take_load_requests());
+        if let Some(changes) = &mut changes {
+            let mut changed = Vec::new();
+            while let Ok(section) = changes.try_recv() {
+                if !changed.contains(&section) {
+                    changed.push(section);
+                }
             }
-        })?;
+            loader.load(&changed);
+        }
+        while let Some(load_event) = loader.try_recv() {
+            state.apply_load_event(load_event);
+        }
 
-        if event::poll(std::time::Duration::from_millis(settings.poll_duration_ms))? {
-            if let event::Event::Key(key) = event::read()? {
-                if let event::KeyCode::Char('q') = key.code {
+        state.screen = terminal
+            .draw(|f| match state.is_loading {
+                true => {
+                    draw_loading_screen(f, &mut state);
+                }
+                false => {
+                    draw_app(f, &state);
+                }
+            })?
+            .area;
+
+        let poll_duration = Duration::from_millis(settings.poll_duration_ms);
+        match tokio::time::timeout(poll_duration, events.recv()).await {
+            Ok(None) => break,
+            Ok(Some(event::Event::Mouse(mouse))) if !state.is_loading => {
+                mouse_map(mouse, &mut state);
+                if state.should_quit {
                     break;
                 }
+            }
+            Ok(Some(event::Event::Key(key))) => {
                 global_key_map(key, &mut state);
+                if state.should_quit {
+                    break;
+                }
+                for effect in state.take_effects() {
+                    state.status_message = Some(perform(effect));
+                }
             }
+            Ok(Some(_)) | Err(_) => {}
         }
     }
 
@@ -100,29 +275,25 @@ fn draw_loading_screen<B: tui::backend::Backend>(f: &mut Frame<B>, state: &mut S
             Constraint::Percentage(49),
         ])
         .split(f.size());
-    render_loading_screen(f, chunks[1], &state);
+    render_loading_screen(f, chunks[1], state);
 }
 
 fn draw_app<B: tui::backend::Backend>(f: &mut Frame<B>, state: &State) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(2)
-        .constraints([
-            Constraint::Length(9),
-            Constraint::Length(3),
-            Constraint::Min(0),
-            Constraint::Length(3),
-        ])
-        .split(f.size());
+    let chunks = ui::app_layout(f.size());
 
-    render_banner(f, chunks[0]);
-    render_tabs(f, chunks[1], state.selected_tab.index());
+    render_banner(f, chunks[0], state.theme);
+    render_tabs(f, chunks[1], state);
 
     match state.selected_tab {
-        TabsHeadings::AboutMe => render_about_me(f, chunks[2], &state),
-        TabsHeadings::ContactDetails => render_contact_details(f, chunks[2]),
-        TabsHeadings::EmploymentAndEducation => render_employment(f, chunks[2], &state),
+        TabsHeadings::AboutMe => render_about_me(f, chunks[2], state),
+        TabsHeadings::ContactDetails => render_contact_details(f, chunks[2], state),
+        TabsHeadings::EmploymentAndEducation => render_employment(f, chunks[2], state),
     }
+    render_load_errors(f, chunks[2], state);
 
-    render_keymap(f, chunks[3], &state);
+    render_keymap(f, chunks[3], state);
+
+    if state.help_open {
+        render_help(f, f.size(), state);
+    }
 }
@@ -0,0 +1,533 @@
+//! The community JSON Resume schema (<https://jsonresume.org/schema>), so
+//! resumes can be imported from and exported to other resume tooling.
+
+use serde::{Deserialize, Serialize};
+
+use super::{Award, Profile, Project, Resume, Skill};
+use crate::{
+    about_me::AboutMe,
+    contact::{profile_url, ContactDetails, Link, GITHUB_URL, LINKEDIN_URL},
+    employment::{Details, Education, Employment},
+};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct JsonResume {
+    #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    pub basics: Basics,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub work: Vec<Work>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub education: Vec<JsonEducation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<JsonSkill>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<JsonProject>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub awards: Vec<JsonAward>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Basics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<JsonProfile>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct JsonProfile {
+    pub network: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Work {
+    pub name: String,
+    pub position: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub start_date: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct JsonEducation {
+    pub institution: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub area: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub study_type: Option<String>,
+    pub start_date: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub courses: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct JsonSkill {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct JsonProject {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct JsonAward {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub awarder: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// JSON Resume leaves `endDate` out for current positions.
+fn end_date(end_date: Option<String>) -> String {
+    end_date.unwrap_or_else(|| String::from("Present"))
+}
+
+fn non_empty(values: Vec<Option<String>>) -> Option<String> {
+    let values: Vec<String> = values
+        .into_iter()
+        .flatten()
+        .filter(|value| !value.is_empty())
+        .collect();
+    match values.is_empty() {
+        true => None,
+        false => Some(values.join(", ")),
+    }
+}
+
+impl From<Basics> for ContactDetails {
+    fn from(basics: Basics) -> Self {
+        let mut contact = ContactDetails {
+            email: basics.email,
+            phone: basics.phone,
+            website: basics.url,
+            location: basics.location.and_then(|location| {
+                non_empty(vec![location.city, location.region, location.country_code])
+            }),
+            ..ContactDetails::default()
+        };
+
+        for profile in basics.profiles {
+            let handle = profile.username.clone().or(profile.url.clone());
+            match profile.network.to_ascii_lowercase().as_str() {
+                "github" if contact.github.is_none() => contact.github = handle,
+                "linkedin" if contact.linkedin.is_none() => contact.linkedin = handle,
+                _ => {
+                    if let Some(url) = profile.url {
+                        contact.links.push(Link {
+                            label: profile.network,
+                            url,
+                        });
+                    }
+                }
+            }
+        }
+        contact
+    }
+}
+
+impl From<Work> for Employment {
+    fn from(work: Work) -> Self {
+        Self {
+            employer: work.name,
+            role: work.position,
+            start_date: work.start_date,
+            end_date: end_date(work.end_date),
+            details: Details {
+                summary: work.summary,
+                achievements: work.highlights,
+                technologies: Vec::new(),
+                location: work.location,
+                link: work.url,
+            },
+        }
+    }
+}
+
+impl From<JsonEducation> for Education {
+    fn from(education: JsonEducation) -> Self {
+        let qualification = match (education.study_type, education.area) {
+            (Some(study_type), Some(area)) => format!("{} in {}", study_type, area),
+            (Some(qualification), None) | (None, Some(qualification)) => qualification,
+            (None, None) => String::new(),
+        };
+
+        Self {
+            educator: education.institution,
+            qualification,
+            start_date: education.start_date,
+            end_date: end_date(education.end_date),
+            details: Details {
+                summary: education.score.map(|score| format!("Score: {}", score)),
+                achievements: education.courses,
+                technologies: Vec::new(),
+                location: None,
+                link: education.url,
+            },
+        }
+    }
+}
+
+impl From<JsonResume> for Resume {
+    fn from(json: JsonResume) -> Self {
+        let name = json.basics.name.clone();
+        let label = json.basics.label.clone();
+        let about_me = json.basics.summary.clone().unwrap_or_default();
+
+        let profile = Profile {
+            name,
+            label,
+            skills: json
+                .skills
+                .into_iter()
+                .map(|skill| Skill {
+                    name: skill.name,
+                    level: skill.level,
+                    keywords: skill.keywords,
+                })
+                .collect(),
+            projects: json
+                .projects
+                .into_iter()
+                .map(|project| Project {
+                    name: project.name,
+                    description: project.description,
+                    highlights: project.highlights,
+                    keywords: project.keywords,
+                    start_date: project.start_date,
+                    end_date: project.end_date,
+                    link: project.url,
+                })
+                .collect(),
+            awards: json
+                .awards
+                .into_iter()
+                .map(|award| Award {
+                    title: award.title,
+                    date: award.date,
+                    awarder: award.awarder,
+                    summary: award.summary,
+                })
+                .collect(),
+        };
+
+        Self {
+            profile,
+            about: AboutMe { about_me },
+            contact: json.basics.into(),
+            employment: json.work.into_iter().map(Employment::from).collect(),
+            education: json.education.into_iter().map(Education::from).collect(),
+        }
+    }
+}
+
+pub const SCHEMA_URL: &str =
+    "https://raw.githubusercontent.com/jsonresume/resume-schema/v1.0.0/schema.json";
+
+fn is_url(value: &str) -> bool {
+    value.starts_with("http://") || value.starts_with("https://")
+}
+
+fn profile(network: &str, handle: &str, base: &str) -> JsonProfile {
+    JsonProfile {
+        network: network.to_string(),
+        username: (!is_url(handle)).then(|| handle.trim_start_matches('@').to_string()),
+        url: Some(profile_url(handle, base)),
+    }
+}
+
+/// The inverse of [`end_date`].
+fn optional_end_date(end_date: &str) -> Option<String> {
+    match end_date {
+        "" | "Present" => None,
+        end_date => Some(end_date.to_string()),
+    }
+}
+
+impl From<&ContactDetails> for Basics {
+    fn from(contact: &ContactDetails) -> Self {
+        let mut profiles = Vec::new();
+        if let Some(github) = &contact.github {
+            profiles.push(profile("GitHub", github, GITHUB_URL));
+        }
+        if let Some(linkedin) = &contact.linkedin {
+            profiles.push(profile("LinkedIn", linkedin, LINKEDIN_URL));
+        }
+        profiles.extend(contact.links.iter().map(|link| JsonProfile {
+            network: link.label.clone(),
+            username: None,
+            url: Some(link.url.clone()),
+        }));
+
+        Self {
+            email: contact.email.clone(),
+            phone: contact.phone.clone(),
+            url: contact.website.clone(),
+            location: contact.location.clone().map(|city| Location {
+                city: Some(city),
+                ..Location::default()
+            }),
+            profiles,
+            ..Basics::default()
+        }
+    }
+}
+
+impl From<&Employment> for Work {
+    fn from(employment: &Employment) -> Self {
+        let details = &employment.details;
+        Self {
+            name: employment.employer.clone(),
+            position: employment.role.clone(),
+            location: details.location.clone(),
+            url: details.link.clone(),
+            start_date: employment.start_date.clone(),
+            end_date: optional_end_date(&employment.end_date),
+            summary: details.summary.clone(),
+            highlights: details.achievements.clone(),
+        }
+    }
+}
+
+impl From<&Education> for JsonEducation {
+    fn from(education: &Education) -> Self {
+        let details = &education.details;
+        let (study_type, area) = match education.qualification.split_once(" in ") {
+            Some((study_type, area)) => (Some(study_type.to_string()), Some(area.to_string())),
+            None if education.qualification.is_empty() => (None, None),
+            None => (Some(education.qualification.clone()), None),
+        };
+
+        Self {
+            institution: education.educator.clone(),
+            url: details.link.clone(),
+            area,
+            study_type,
+            start_date: education.start_date.clone(),
+            end_date: optional_end_date(&education.end_date),
+            score: details
+                .summary
+                .as_deref()
+                .and_then(|summary| summary.strip_prefix("Score: "))
+                .map(String::from),
+            courses: details.achievements.clone(),
+        }
+    }
+}
+
+impl From<&Resume> for JsonResume {
+    fn from(resume: &Resume) -> Self {
+        let profile = &resume.profile;
+        let about_me = &resume.about.about_me;
+
+        Self {
+            schema: Some(SCHEMA_URL.to_string()),
+            basics: Basics {
+                name: profile.name.clone(),
+                label: profile.label.clone(),
+                summary: (!about_me.is_empty()).then(|| about_me.clone()),
+                ..Basics::from(&resume.contact)
+            },
+            work: resume.employment.iter().map(Work::from).collect(),
+            education: resume.education.iter().map(JsonEducation::from).collect(),
+            skills: profile
+                .skills
+                .iter()
+                .map(|skill| JsonSkill {
+                    name: skill.name.clone(),
+                    level: skill.level.clone(),
+                    keywords: skill.keywords.clone(),
+                })
+                .collect(),
+            projects: profile
+                .projects
+                .iter()
+                .map(|project| JsonProject {
+                    name: project.name.clone(),
+                    description: project.description.clone(),
+                    highlights: project.highlights.clone(),
+                    keywords: project.keywords.clone(),
+                    start_date: project.start_date.clone(),
+                    end_date: project.end_date.clone(),
+                    url: project.link.clone(),
+                })
+                .collect(),
+            awards: profile
+                .awards
+                .iter()
+                .map(|award| JsonAward {
+                    title: award.title.clone(),
+                    date: award.date.clone(),
+                    awarder: award.awarder.clone(),
+                    summary: award.summary.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON_RESUME: &[u8] = br#"{
+        "$schema": "https://raw.githubusercontent.com/jsonresume/resume-schema/v1.0.0/schema.json",
+        "basics": {
+            "name": "Ada Lovelace",
+            "label": "Analyst",
+            "email": "ada@example.com",
+            "summary": "Writes programs for engines that do not exist yet.",
+            "location": {"city": "London", "countryCode": "GB"},
+            "profiles": [
+                {"network": "GitHub", "username": "ada", "url": "https://github.com/ada"},
+                {"network": "Mastodon", "username": "ada", "url": "https://mastodon.social/@ada"}
+            ]
+        },
+        "work": [{
+            "name": "Analytical Engine Ltd",
+            "position": "Programmer",
+            "startDate": "1842-01-01",
+            "highlights": ["Published the first algorithm"]
+        }],
+        "education": [{
+            "institution": "Home",
+            "area": "Mathematics",
+            "studyType": "Tutoring",
+            "startDate": "1830",
+            "endDate": "1835",
+            "score": "A"
+        }],
+        "skills": [{"name": "Mathematics", "level": "Master", "keywords": ["Calculus"]}],
+        "projects": [{"name": "Note G", "url": "https://example.com/note-g"}],
+        "awards": [{"title": "Fellowship", "awarder": "Society"}],
+        "meta": {"version": "v1.0.0"}
+    }"#;
+
+    #[test]
+    fn test_import_json_resume() {
+        let resume = Resume::from_json(JSON_RESUME, None).unwrap();
+
+        assert_eq!(resume.profile.name.as_deref(), Some("Ada Lovelace"));
+        assert_eq!(resume.profile.label.as_deref(), Some("Analyst"));
+        assert!(resume.about.about_me.starts_with("Writes programs"));
+
+        assert_eq!(resume.contact.email.as_deref(), Some("ada@example.com"));
+        assert_eq!(resume.contact.github.as_deref(), Some("ada"));
+        assert_eq!(resume.contact.location.as_deref(), Some("London, GB"));
+        assert_eq!(
+            resume.contact.links,
+            vec![Link {
+                label: String::from("Mastodon"),
+                url: String::from("https://mastodon.social/@ada"),
+            }]
+        );
+
+        let work = &resume.employment[0];
+        assert_eq!(work.employer, "Analytical Engine Ltd");
+        assert_eq!(work.end_date, "Present");
+        assert_eq!(
+            work.details.achievements,
+            vec!["Published the first algorithm"]
+        );
+
+        let education = &resume.education[0];
+        assert_eq!(education.qualification, "Tutoring in Mathematics");
+        assert_eq!(education.details.summary.as_deref(), Some("Score: A"));
+
+        assert_eq!(resume.profile.skills[0].keywords, vec!["Calculus"]);
+        assert_eq!(
+            resume.profile.projects[0].link.as_deref(),
+            Some("https://example.com/note-g")
+        );
+        assert_eq!(resume.profile.awards[0].awarder.as_deref(), Some("Society"));
+    }
+
+    #[test]
+    fn test_export_round_trips() {
+        let resume = Resume::from_json(JSON_RESUME, None).unwrap();
+        let exported = serde_json::to_vec(&JsonResume::from(&resume)).unwrap();
+        let reimported = Resume::from_json(&exported, None).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&reimported).unwrap(),
+            serde_json::to_value(&resume).unwrap()
+        );
+
+        let json: serde_json::Value = serde_json::from_slice(&exported).unwrap();
+        assert_eq!(
+            json["basics"]["profiles"][0]["url"],
+            "https://github.com/ada"
+        );
+        assert_eq!(json["education"][0]["studyType"], "Tutoring");
+        assert!(json["work"][0].get("endDate").is_none());
+    }
+}
@@ -0,0 +1,259 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    about_me::AboutMe,
+    contact::ContactDetails,
+    employment::{Details, Education, Employment},
+};
+
+mod json_resume;
+
+pub use json_resume::JsonResume;
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Skill {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Project {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+impl Project {
+    /// The project as an employment-style entry, for layouts that share
+    /// their rendering.
+    pub fn details(&self) -> Details {
+        Details {
+            summary: self.description.clone(),
+            achievements: self.highlights.clone(),
+            technologies: self.keywords.clone(),
+            location: None,
+            link: self.link.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Award {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub awarder: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// Everything about the person that doesn't have a tab of its own. Stored
+/// as `profile.json`, which older data sets don't have.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct Profile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// A one-line headline such as "Software Engineer".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<Skill>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<Project>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub awards: Vec<Award>,
+}
+
+/// The whole resume as one document. Every section is optional so a
+/// document only needs the parts it has.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct Resume {
+    #[serde(flatten)]
+    pub profile: Profile,
+    pub about: AboutMe,
+    pub contact: ContactDetails,
+    pub employment: Vec<Employment>,
+    pub education: Vec<Education>,
+}
+
+/// Our own resume document as written, spelled out rather than flattened so
+/// that unknown keys, usually typos, are reported instead of ignored.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct NativeResume {
+    name: Option<String>,
+    label: Option<String>,
+    skills: Vec<Skill>,
+    projects: Vec<Project>,
+    awards: Vec<Award>,
+    about: AboutMe,
+    contact: ContactDetails,
+    employment: Vec<Employment>,
+    education: Vec<Education>,
+}
+
+impl From<NativeResume> for Resume {
+    fn from(native: NativeResume) -> Self {
+        Self {
+            profile: Profile {
+                name: native.name,
+                label: native.label,
+                skills: native.skills,
+                projects: native.projects,
+                awards: native.awards,
+            },
+            about: native.about,
+            contact: native.contact,
+            employment: native.employment,
+            education: native.education,
+        }
+    }
+}
+
+/// Which schema a single-file resume follows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DocumentFormat {
+    /// The format written by `app export --format json`.
+    Native,
+    /// JSON Resume (<https://jsonresume.org/schema>).
+    JsonResume,
+}
+
+impl DocumentFormat {
+    /// JSON Resume documents name their schema in `$schema`; anything else is
+    /// taken to be our own format.
+    fn detect(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+        #[derive(Deserialize)]
+        struct Schema {
+            #[serde(rename = "$schema")]
+            schema: Option<String>,
+        }
+
+        let schema: Schema = serde_json::from_slice(bytes)?;
+        match schema.schema {
+            Some(schema) if schema.contains("jsonresume") => Ok(Self::JsonResume),
+            _ => Ok(Self::Native),
+        }
+    }
+}
+
+impl FromStr for DocumentFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "native" => Ok(Self::Native),
+            "json-resume" => Ok(Self::JsonResume),
+            _ => Err(format!(
+                "unknown document format {:?}, expected native or json-resume",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for DocumentFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Native => write!(f, "native"),
+            Self::JsonResume => write!(f, "json-resume"),
+        }
+    }
+}
+
+impl Resume {
+    /// Reads a whole resume from one document in `format`, or in the format
+    /// its `$schema` names when `None`.
+    pub fn from_json(
+        bytes: &[u8],
+        format: Option<DocumentFormat>,
+    ) -> Result<Self, serde_json::Error> {
+        let format = match format {
+            Some(format) => format,
+            None => DocumentFormat::detect(bytes)?,
+        };
+        match format {
+            DocumentFormat::Native => Ok(serde_json::from_slice::<NativeResume>(bytes)?.into()),
+            DocumentFormat::JsonResume => Ok(serde_json::from_slice::<JsonResume>(bytes)?.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json_reads_native_document() {
+        let resume = Resume::from_json(
+            br#"{
+                "name": "Ada",
+                "about": {"about_me": "Hello"},
+                "employment": [
+                    {"employer": "Acme", "role": "Engineer", "start_date": "2020", "end_date": "Present"}
+                ],
+                "skills": [{"name": "Rust"}]
+            }"#,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(resume.about.about_me, "Hello");
+        assert_eq!(resume.employment[0].employer, "Acme");
+        assert!(resume.education.is_empty());
+        assert_eq!(resume.profile.skills[0].name, "Rust");
+        assert_eq!(resume.profile.name.as_deref(), Some("Ada"));
+    }
+
+    #[test]
+    fn test_from_json_rejects_unknown_keys() {
+        let err = Resume::from_json(br#"{"employmnet": []}"#, None).unwrap_err();
+        assert!(err.to_string().contains("unknown field `employmnet`"));
+
+        // Without `$schema` a JSON Resume has to be asked for.
+        let json_resume = br#"{"basics": {"name": "Ada"}, "work": []}"#;
+        assert!(Resume::from_json(json_resume, None).is_err());
+        let resume = Resume::from_json(json_resume, Some(DocumentFormat::JsonResume)).unwrap();
+        assert_eq!(resume.profile.name.as_deref(), Some("Ada"));
+    }
+
+    #[test]
+    fn test_from_json_detects_json_resume_schema() {
+        let resume = Resume::from_json(
+            br#"{
+                "$schema": "https://raw.githubusercontent.com/jsonresume/resume-schema/v1.0.0/schema.json",
+                "basics": {"name": "Ada"}
+            }"#,
+            None,
+        )
+        .unwrap();
+        assert_eq!(resume.profile.name.as_deref(), Some("Ada"));
+    }
+
+    #[test]
+    fn test_from_json_reports_position_of_errors() {
+        let err = Resume::from_json(b"{\"employment\": [1]}", None).unwrap_err();
+        assert_eq!(err.line(), 1);
+    }
+}
@@ -0,0 +1,371 @@
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+/// Turns the bytes a remote terminal sends into crossterm events, which is
+/// what crossterm itself does for the local terminal.
+#[derive(Default)]
+pub struct InputParser {
+    pending: Vec<u8>,
+    /// Skipping the rest of a sequence that ran past `MAX_SEQUENCE_LEN`.
+    discarding: bool,
+}
+
+/// Far longer than any key or mouse report a terminal sends.
+const MAX_SEQUENCE_LEN: usize = 64;
+
+/// Whether `byte` ends a `ESC [` sequence.
+fn is_final(byte: &u8) -> bool {
+    (0x40..=0x7e).contains(byte)
+}
+
+impl InputParser {
+    pub fn feed(&mut self, mut bytes: &[u8]) -> Vec<Event> {
+        if self.discarding {
+            match bytes.iter().position(is_final) {
+                Some(end) => {
+                    self.discarding = false;
+                    bytes = &bytes[end + 1..];
+                }
+                None => return Vec::new(),
+            }
+        }
+
+        self.pending.extend_from_slice(bytes);
+        let mut events = Vec::new();
+        let mut start = 0;
+        while start < self.pending.len() {
+            match parse(&self.pending[start..]) {
+                Parsed::Event(event, len) => {
+                    events.extend(event);
+                    start += len;
+                }
+                Parsed::Incomplete => {
+                    // Only an endless `ESC [` gets this long. Drop it, and
+                    // whatever of it is still to come, rather than buffer it.
+                    if self.pending.len() - start > MAX_SEQUENCE_LEN {
+                        start = self.pending.len();
+                        self.discarding = true;
+                    }
+                    break;
+                }
+            }
+        }
+        self.pending.drain(..start);
+        events
+    }
+}
+
+enum Parsed {
+    /// An event, or `None` for a sequence we recognise but ignore, and how
+    /// many bytes it took.
+    Event(Option<Event>, usize),
+    Incomplete,
+}
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> Option<Event> {
+    Some(Event::Key(KeyEvent::new(code, modifiers)))
+}
+
+fn parse(bytes: &[u8]) -> Parsed {
+    match bytes[0] {
+        0x1b => parse_escape(bytes),
+        b'\r' | b'\n' => Parsed::Event(key(KeyCode::Enter, KeyModifiers::NONE), 1),
+        b'\t' => Parsed::Event(key(KeyCode::Tab, KeyModifiers::NONE), 1),
+        0x7f | 0x08 => Parsed::Event(key(KeyCode::Backspace, KeyModifiers::NONE), 1),
+        0x00 => Parsed::Event(key(KeyCode::Char(' '), KeyModifiers::CONTROL), 1),
+        c @ 0x01..=0x1a => {
+            let letter = (c - 1 + b'a') as char;
+            Parsed::Event(key(KeyCode::Char(letter), KeyModifiers::CONTROL), 1)
+        }
+        0x1c..=0x1f => Parsed::Event(None, 1),
+        _ => parse_char(bytes, KeyModifiers::NONE, 0),
+    }
+}
+
+/// Decodes one UTF-8 character starting at `bytes[offset]`.
+fn parse_char(bytes: &[u8], modifiers: KeyModifiers, offset: usize) -> Parsed {
+    let len = match bytes[offset] {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return Parsed::Event(None, offset + 1),
+    };
+    let Some(encoded) = bytes.get(offset..offset + len) else {
+        return Parsed::Incomplete;
+    };
+    match std::str::from_utf8(encoded)
+        .ok()
+        .and_then(|s| s.chars().next())
+    {
+        Some(c) => {
+            let modifiers = match c.is_uppercase() {
+                true => modifiers | KeyModifiers::SHIFT,
+                false => modifiers,
+            };
+            Parsed::Event(key(KeyCode::Char(c), modifiers), offset + len)
+        }
+        None => Parsed::Event(None, offset + len),
+    }
+}
+
+fn parse_escape(bytes: &[u8]) -> Parsed {
+    match bytes.get(1) {
+        // A lone escape is the Esc key: terminals send whole sequences at once.
+        None => Parsed::Event(key(KeyCode::Esc, KeyModifiers::NONE), 1),
+        Some(b'[') => parse_csi(bytes),
+        Some(b'O') => match bytes.get(2) {
+            None => Parsed::Incomplete,
+            Some(&final_byte) => Parsed::Event(
+                final_key(final_byte).and_then(|code| key(code, KeyModifiers::NONE)),
+                3,
+            ),
+        },
+        Some(0x1b) => Parsed::Event(key(KeyCode::Esc, KeyModifiers::NONE), 1),
+        // Alt+key arrives as escape followed by the key.
+        Some(_) => parse_char(bytes, KeyModifiers::ALT, 1),
+    }
+}
+
+/// The key named by the last byte of `ESC [ A` and `ESC O A` style sequences.
+fn final_key(byte: u8) -> Option<KeyCode> {
+    match byte {
+        b'A' => Some(KeyCode::Up),
+        b'B' => Some(KeyCode::Down),
+        b'C' => Some(KeyCode::Right),
+        b'D' => Some(KeyCode::Left),
+        b'H' => Some(KeyCode::Home),
+        b'F' => Some(KeyCode::End),
+        b'P' => Some(KeyCode::F(1)),
+        b'Q' => Some(KeyCode::F(2)),
+        b'R' => Some(KeyCode::F(3)),
+        b'S' => Some(KeyCode::F(4)),
+        _ => None,
+    }
+}
+
+/// The key named by the number in `ESC [ 5 ~` style sequences.
+fn tilde_key(number: u16) -> Option<KeyCode> {
+    match number {
+        1 | 7 => Some(KeyCode::Home),
+        2 => Some(KeyCode::Insert),
+        3 => Some(KeyCode::Delete),
+        4 | 8 => Some(KeyCode::End),
+        5 => Some(KeyCode::PageUp),
+        6 => Some(KeyCode::PageDown),
+        15 => Some(KeyCode::F(5)),
+        17..=21 => Some(KeyCode::F((number - 11) as u8)),
+        23 | 24 => Some(KeyCode::F((number - 12) as u8)),
+        _ => None,
+    }
+}
+
+/// xterm encodes modifiers as one plus a bit set: shift 1, alt 2, ctrl 4.
+fn modifiers(parameter: u16) -> KeyModifiers {
+    let bits = parameter.saturating_sub(1);
+    let mut modifiers = KeyModifiers::NONE;
+    if bits & 1 != 0 {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if bits & 2 != 0 {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if bits & 4 != 0 {
+        modifiers |= KeyModifiers::CONTROL;
+    }
+    modifiers
+}
+
+/// A mouse report: xterm's button code, which also carries the modifiers,
+/// and a 1-based position.
+fn mouse(code: u16, column: u16, row: u16, released: bool) -> Option<Event> {
+    let mut modifiers = KeyModifiers::NONE;
+    if code & 4 != 0 {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if code & 8 != 0 {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if code & 16 != 0 {
+        modifiers |= KeyModifiers::CONTROL;
+    }
+
+    let button = match code & 3 {
+        1 => MouseButton::Middle,
+        2 => MouseButton::Right,
+        _ => MouseButton::Left,
+    };
+    let kind = if code & 64 != 0 {
+        match code & 3 {
+            0 => MouseEventKind::ScrollUp,
+            1 => MouseEventKind::ScrollDown,
+            2 => MouseEventKind::ScrollLeft,
+            _ => MouseEventKind::ScrollRight,
+        }
+    } else if code & 32 != 0 {
+        match code & 3 {
+            3 => MouseEventKind::Moved,
+            _ => MouseEventKind::Drag(button),
+        }
+    } else if released || code & 3 == 3 {
+        // The old encoding reports every release as button 3.
+        MouseEventKind::Up(button)
+    } else {
+        MouseEventKind::Down(button)
+    };
+
+    Some(Event::Mouse(MouseEvent {
+        kind,
+        column: column.saturating_sub(1),
+        row: row.saturating_sub(1),
+        modifiers,
+    }))
+}
+
+fn parse_csi(bytes: &[u8]) -> Parsed {
+    // The old mouse encoding, `ESC [ M` and three bytes offset by 32.
+    if bytes.get(2) == Some(&b'M') {
+        return match bytes.get(3..6) {
+            Some(&[code, column, row]) => {
+                let [code, column, row] = [code, column, row].map(|b| b.saturating_sub(32) as u16);
+                Parsed::Event(mouse(code, column, row, false), 6)
+            }
+            _ => Parsed::Incomplete,
+        };
+    }
+
+    let Some(end) = bytes[2..].iter().position(is_final) else {
+        return Parsed::Incomplete;
+    };
+    let len = end + 3;
+    let final_byte = bytes[len - 1];
+
+    // SGR mouse reports, `ESC [ < code ; column ; row M`, or `m` on release.
+    if bytes[2] == b'<' {
+        let numbers: Vec<u16> = std::str::from_utf8(&bytes[3..len - 1])
+            .unwrap_or_default()
+            .split(';')
+            .filter_map(|number| number.parse().ok())
+            .collect();
+        let event = match (&numbers[..], final_byte) {
+            (&[code, column, row], b'M' | b'm') => mouse(code, column, row, final_byte == b'm'),
+            _ => None,
+        };
+        return Parsed::Event(event, len);
+    }
+    let parameters: Vec<u16> = std::str::from_utf8(&bytes[2..len - 1])
+        .unwrap_or_default()
+        .split(';')
+        .map(|parameter| parameter.parse().unwrap_or(0))
+        .collect();
+    let modifiers = modifiers(parameters.get(1).copied().unwrap_or(1));
+
+    let code = match final_byte {
+        b'~' => tilde_key(parameters[0]),
+        b'Z' => return Parsed::Event(key(KeyCode::BackTab, KeyModifiers::SHIFT), len),
+        byte => final_key(byte),
+    };
+    Parsed::Event(code.and_then(|code| key(code, modifiers)), len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(bytes: &[u8]) -> Vec<(KeyCode, KeyModifiers)> {
+        InputParser::default()
+            .feed(bytes)
+            .into_iter()
+            .map(|event| match event {
+                Event::Key(key) => (key.code, key.modifiers),
+                event => panic!("unexpected {:?}", event),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_plain_and_control_keys() {
+        assert_eq!(
+            keys("jQé\r\x0f\x7f".as_bytes()),
+            vec![
+                (KeyCode::Char('j'), KeyModifiers::NONE),
+                (KeyCode::Char('Q'), KeyModifiers::SHIFT),
+                (KeyCode::Char('é'), KeyModifiers::NONE),
+                (KeyCode::Enter, KeyModifiers::NONE),
+                (KeyCode::Char('o'), KeyModifiers::CONTROL),
+                (KeyCode::Backspace, KeyModifiers::NONE),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escape_sequences() {
+        assert_eq!(
+            keys(b"\x1b[A\x1bOB\x1b[5~\x1b[1;5C\x1b[Z\x1bx\x1b"),
+            vec![
+                (KeyCode::Up, KeyModifiers::NONE),
+                (KeyCode::Down, KeyModifiers::NONE),
+                (KeyCode::PageUp, KeyModifiers::NONE),
+                (KeyCode::Right, KeyModifiers::CONTROL),
+                (KeyCode::BackTab, KeyModifiers::SHIFT),
+                (KeyCode::Char('x'), KeyModifiers::ALT),
+                (KeyCode::Esc, KeyModifiers::NONE),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_sequences_wait_for_the_rest() {
+        let mut parser = InputParser::default();
+        assert!(parser.feed(b"\x1b[1;").is_empty());
+        assert_eq!(
+            parser.feed(b"2D"),
+            vec![Event::Key(KeyEvent::new(
+                KeyCode::Left,
+                KeyModifiers::SHIFT
+            ))]
+        );
+        assert!(parser.feed(&"é".as_bytes()[..1]).is_empty());
+        assert_eq!(parser.feed(&"é".as_bytes()[1..]).len(), 1);
+    }
+
+    #[test]
+    fn test_endless_sequences_are_dropped() {
+        let mut parser = InputParser::default();
+        assert!(parser.feed(b"\x1b[").is_empty());
+        for _ in 0..1000 {
+            assert!(parser.feed(&[b'1'; 100]).is_empty());
+            assert!(parser.pending.len() <= MAX_SEQUENCE_LEN);
+        }
+        assert_eq!(
+            parser.feed(b";2~j"),
+            vec![Event::Key(KeyEvent::new(
+                KeyCode::Char('j'),
+                KeyModifiers::NONE
+            ))]
+        );
+    }
+
+    #[test]
+    fn test_mouse_reports() {
+        let events =
+            InputParser::default().feed(b"\x1b[<0;10;5M\x1b[<0;10;5m\x1b[<65;1;1M\x1b[M !!");
+        let kinds: Vec<_> = events
+            .into_iter()
+            .map(|event| match event {
+                Event::Mouse(mouse) => (mouse.kind, mouse.column, mouse.row),
+                event => panic!("unexpected {:?}", event),
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (MouseEventKind::Down(MouseButton::Left), 9, 4),
+                (MouseEventKind::Up(MouseButton::Left), 9, 4),
+                (MouseEventKind::ScrollDown, 0, 0),
+                (MouseEventKind::Down(MouseButton::Left), 0, 0),
+            ]
+        );
+    }
+}
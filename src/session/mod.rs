@@ -0,0 +1,242 @@
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crossterm::event::Event;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tui::{
+    backend::{Backend, CrosstermBackend},
+    buffer::Cell,
+    layout::Rect,
+    Terminal,
+};
+
+use crate::{effects, keymap::KeyBindings, settings::Settings};
+
+mod input;
+pub mod ssh;
+pub mod telnet;
+pub mod web;
+
+use input::InputParser;
+
+/// How long a viewer can go without sending anything before they are
+/// disconnected, so forgotten tabs and idle sockets don't hold every
+/// connection slot.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// What a network transport reports about its viewer once it has decoded
+/// its own protocol.
+#[derive(Debug, PartialEq)]
+pub enum SessionEvent {
+    /// Bytes typed into the viewer's terminal.
+    Input(Vec<u8>),
+    Resize {
+        width: u16,
+        height: u16,
+    },
+}
+
+/// Collects what the `tui` backend writes and hands it to the transport a
+/// frame at a time, on each flush. Once the viewer has gone the output is
+/// dropped; the session notices when its input closes.
+#[derive(Clone)]
+pub struct ChannelWriter {
+    buffer: Vec<u8>,
+    output: UnboundedSender<Vec<u8>>,
+}
+
+impl ChannelWriter {
+    pub fn new(output: UnboundedSender<Vec<u8>>) -> Self {
+        Self {
+            buffer: Vec::new(),
+            output,
+        }
+    }
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            let _ = self.output.send(std::mem::take(&mut self.buffer));
+        }
+        Ok(())
+    }
+}
+
+/// A `tui` backend drawing into a `ChannelWriter`. The escape sequences are
+/// crossterm's, but the size is whatever the viewer last reported rather
+/// than the size of the terminal this process runs in. Frames that change
+/// nothing send nothing, rather than a few bytes of resets every tick.
+pub struct ChannelBackend {
+    inner: CrosstermBackend<ChannelWriter>,
+    size: Arc<Mutex<Rect>>,
+    cursor: (u16, u16),
+    cursor_hidden: bool,
+}
+
+impl Backend for ChannelBackend {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        let mut content = content.peekable();
+        match content.peek() {
+            Some(_) => self.inner.draw(content),
+            None => Ok(()),
+        }
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        if self.cursor_hidden {
+            return Ok(());
+        }
+        self.cursor_hidden = true;
+        self.inner.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        if !self.cursor_hidden {
+            return Ok(());
+        }
+        self.cursor_hidden = false;
+        self.inner.show_cursor()
+    }
+
+    fn get_cursor(&mut self) -> io::Result<(u16, u16)> {
+        Ok(self.cursor)
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.cursor = (x, y);
+        self.inner.set_cursor(x, y)
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.inner.clear()
+    }
+
+    fn size(&self) -> io::Result<Rect> {
+        Ok(*self.size.lock().unwrap())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Backend::flush(&mut self.inner)
+    }
+}
+
+/// Switches to the alternate screen, hides the cursor and asks for mouse
+/// clicks and the wheel to be reported in SGR form.
+const ENTER_SCREEN: &[u8] = b"\x1b[?1049h\x1b[?25l\x1b[?1000h\x1b[?1006h";
+const LEAVE_SCREEN: &[u8] = b"\x1b[?1006l\x1b[?1000l\x1b[?25h\x1b[?1049l";
+
+/// Runs the resume for one remote viewer, with its own `State`, until they
+/// quit or `events` closes. Everything drawn is sent to `output`; the
+/// transport hangs up once `run` returns.
+pub async fn run(
+    settings: Arc<Settings>,
+    width: u16,
+    height: u16,
+    mut events: UnboundedReceiver<SessionEvent>,
+    output: UnboundedSender<Vec<u8>>,
+) -> io::Result<()> {
+    let size = Arc::new(Mutex::new(Rect::new(0, 0, width, height)));
+    let backend = ChannelBackend {
+        inner: CrosstermBackend::new(ChannelWriter::new(output.clone())),
+        size: size.clone(),
+        cursor: (0, 0),
+        cursor_hidden: false,
+    };
+    let mut terminal = Terminal::new(backend)?;
+    let _ = output.send(ENTER_SCREEN.to_vec());
+
+    // Decode input as it arrives so `run_app` only ever sees terminal events.
+    let (terminal_events, mut app_events) = mpsc::unbounded_channel::<Event>();
+    let decoder = tokio::spawn(async move {
+        let mut parser = InputParser::default();
+        while let Some(event) = events.recv().await {
+            let decoded = match event {
+                SessionEvent::Input(bytes) => parser.feed(&bytes),
+                SessionEvent::Resize { width, height } => {
+                    *size.lock().unwrap() = Rect::new(0, 0, width, height);
+                    vec![Event::Resize(width, height)]
+                }
+            };
+            for event in decoded {
+                if terminal_events.send(event).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    let key_bindings = KeyBindings::new(&settings.keybindings).unwrap_or_default();
+    let mut effects_out = ChannelWriter::new(output.clone());
+    let res = crate::run_app(
+        &mut terminal,
+        &settings,
+        key_bindings,
+        &mut app_events,
+        None,
+        |effect| effects::perform_remote(effect, &mut effects_out),
+    )
+    .await;
+
+    decoder.abort();
+    // Dropping the terminal restores the cursor, so do it before leaving.
+    drop(terminal);
+    let _ = output.send(LEAVE_SCREEN.to_vec());
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_writer_sends_on_flush() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut writer = ChannelWriter::new(tx);
+        writer.write_all(b"ab").unwrap();
+        writer.write_all(b"c").unwrap();
+        assert!(rx.try_recv().is_err());
+        writer.flush().unwrap();
+        assert_eq!(rx.try_recv().unwrap(), b"abc");
+        writer.flush().unwrap();
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_session_draws_and_quits() {
+        let mut settings = Settings::default();
+        settings.data_source = crate::settings::DataSource::Bundled;
+        settings.use_cache = false;
+        let (events_tx, events) = mpsc::unbounded_channel();
+        let (output, mut frames) = mpsc::unbounded_channel();
+
+        let session = tokio::spawn(run(Arc::new(settings), 100, 40, events, output));
+        events_tx
+            .send(SessionEvent::Resize {
+                width: 120,
+                height: 50,
+            })
+            .unwrap();
+        events_tx.send(SessionEvent::Input(b"q".to_vec())).unwrap();
+        session.await.unwrap().unwrap();
+
+        let mut drawn = Vec::new();
+        while let Ok(frame) = frames.try_recv() {
+            drawn.extend(frame);
+        }
+        assert!(drawn.starts_with(ENTER_SCREEN));
+        assert!(drawn.ends_with(LEAVE_SCREEN));
+        assert!(drawn.len() > ENTER_SCREEN.len() + LEAVE_SCREEN.len());
+    }
+}
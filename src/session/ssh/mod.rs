@@ -0,0 +1,792 @@
+use std::{
+    error::Error,
+    fs, io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use clap::Args;
+use ed25519_dalek::SigningKey;
+use rand_core::OsRng;
+use ssh_key::{Algorithm, HashAlg, LineEnding, PrivateKey};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{tcp::OwnedWriteHalf, TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+        Semaphore,
+    },
+    time::Instant,
+};
+
+use super::{SessionEvent, IDLE_TIMEOUT, LEAVE_SCREEN};
+use crate::{config::Config, settings::Settings};
+use transport::{Handshake, Transport, SERVER_VERSION};
+use wire::*;
+
+mod transport;
+mod wire;
+
+/// Settings for `serve --ssh`.
+#[derive(Debug, Clone, Args)]
+pub struct Options {
+    /// Private host key, created on first use [default: ssh_host_ed25519_key
+    /// next to the config file]
+    #[arg(long, value_name = "PATH")]
+    pub host_key: Option<PathBuf>,
+}
+
+/// How long a client has to get from connecting to opening its terminal.
+const LOGIN_GRACE: Duration = Duration::from_secs(30);
+
+/// Our window for the client's keystrokes, topped up once half is used.
+const WINDOW_SIZE: u32 = 1024 * 1024;
+const MAX_DATA_LEN: u32 = 32 * 1024;
+
+/// How much output may wait on a client that has stopped opening its window
+/// before it is disconnected.
+const MAX_PENDING_LEN: usize = 1024 * 1024;
+
+pub fn default_host_key_path() -> Option<PathBuf> {
+    Some(Config::default_path()?.with_file_name("ssh_host_ed25519_key"))
+}
+
+/// Reads the host key at `path`, generating and saving one in OpenSSH's
+/// format if there is none yet, so clients see the same key every time.
+pub fn load_host_key(path: &Path) -> Result<PrivateKey, Box<dyn Error>> {
+    match PrivateKey::read_openssh_file(path) {
+        Ok(key) => Ok(key),
+        Err(ssh_key::Error::Io(io::ErrorKind::NotFound)) => {
+            let key = PrivateKey::random(&mut OsRng, Algorithm::Ed25519)?;
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            key.write_openssh_file(path, LineEnding::LF)
+                .map_err(|err| format!("cannot write {}: {}", path.display(), err))?;
+            eprintln!("generated a new host key in {}", path.display());
+            Ok(key)
+        }
+        Err(err) => Err(format!("cannot read {}: {}", path.display(), err).into()),
+    }
+}
+
+/// Serves an independent copy of the TUI to everyone who connects with
+/// `ssh -p <port> <host>`. There is nothing private here, so any user name
+/// is let in without a password.
+pub async fn serve(
+    settings: Settings,
+    addr: SocketAddr,
+    max_connections: usize,
+    options: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let path = match &options.host_key {
+        Some(path) => path.clone(),
+        None => {
+            default_host_key_path().ok_or("no config directory for the host key, use --host-key")?
+        }
+    };
+    let key = load_host_key(&path)?;
+    let host_key = Arc::new(
+        key.key_data()
+            .ed25519()
+            .and_then(|keypair| SigningKey::try_from(keypair).ok())
+            .ok_or_else(|| format!("{} is not an ed25519 key", path.display()))?,
+    );
+
+    let settings = Arc::new(settings);
+    let slots = Arc::new(Semaphore::new(max_connections));
+    let listener = TcpListener::bind(addr).await?;
+    eprintln!(
+        "serving the resume over ssh on {}, host key {}",
+        listener.local_addr()?,
+        key.fingerprint(HashAlg::Sha256)
+    );
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let settings = settings.clone();
+        let host_key = host_key.clone();
+        match slots.clone().try_acquire_owned() {
+            Ok(slot) => {
+                tokio::spawn(async move {
+                    if let Err(err) = Connection::run(stream, settings, host_key).await {
+                        eprintln!("ssh {}: {}", peer, err);
+                    }
+                    drop(slot);
+                });
+            }
+            Err(_) => {
+                tokio::spawn(turn_away(stream));
+            }
+        }
+    }
+}
+
+/// Tells a client we are full. A DISCONNECT straight after the version
+/// exchange is shown to the user by OpenSSH.
+async fn turn_away(mut stream: TcpStream) -> io::Result<()> {
+    stream
+        .write_all(format!("{}\r\n", SERVER_VERSION).as_bytes())
+        .await?;
+    let disconnect = Writer::new(MSG_DISCONNECT)
+        .u32(DISCONNECT_TOO_MANY_CONNECTIONS)
+        .string("Too many viewers right now, please try again later")
+        .string("")
+        .finish();
+    stream
+        .write_all(&Transport::default().seal(&disconnect))
+        .await?;
+    stream.shutdown().await?;
+
+    // Closing with the client's KEXINIT unread would reset the connection
+    // before it reads the DISCONNECT, so wait for it to hang up first.
+    let mut discard = [0; 1024];
+    let _ = tokio::time::timeout(Duration::from_secs(5), async {
+        while stream.read(&mut discard).await? > 0 {}
+        Ok::<_, io::Error>(())
+    })
+    .await;
+    Ok(())
+}
+
+enum KeyExchange {
+    /// The client's KEXINIT arrived; waiting for its ECDH public key.
+    AwaitingInit { client: Vec<u8>, server: Vec<u8> },
+    /// We have sent our NEWKEYS; waiting for the client's.
+    AwaitingNewKeys,
+}
+
+/// The one session channel a connection may have.
+struct Channel {
+    remote_id: u32,
+    remote_window: u32,
+    remote_max_packet: u32,
+    local_window: u32,
+    pty: Option<(u16, u16)>,
+    events: Option<UnboundedSender<SessionEvent>>,
+    /// Output waiting for the client to open its window.
+    pending: Vec<u8>,
+    /// The viewer has gone: close the channel once `pending` is sent.
+    closing: bool,
+}
+
+struct Connection {
+    writer: OwnedWriteHalf,
+    transport: Transport,
+    settings: Arc<Settings>,
+    host_key: Arc<SigningKey>,
+    client_version: String,
+    session_id: Option<Vec<u8>>,
+    key_exchange: Option<KeyExchange>,
+    /// Our KEXINIT, when we sent it before the client's.
+    server_kexinit: Option<Vec<u8>>,
+    authenticated: bool,
+    channel: Option<Channel>,
+    output: Option<UnboundedReceiver<Vec<u8>>>,
+    /// When the viewer is disconnected unless they type something first.
+    idle_deadline: Instant,
+}
+
+enum Step {
+    Read(usize),
+    Output(Option<Vec<u8>>),
+    Idle,
+}
+
+async fn recv_output(output: &mut Option<UnboundedReceiver<Vec<u8>>>) -> Option<Vec<u8>> {
+    match output {
+        Some(output) => output.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+impl Connection {
+    async fn run(
+        stream: TcpStream,
+        settings: Arc<Settings>,
+        host_key: Arc<SigningKey>,
+    ) -> io::Result<()> {
+        stream.set_nodelay(true)?;
+        let (mut reader, mut writer) = stream.into_split();
+        writer
+            .write_all(format!("{}\r\n", SERVER_VERSION).as_bytes())
+            .await?;
+
+        let mut transport = Transport::default();
+        let mut chunk = vec![0; 16 * 1024];
+        let client_version = tokio::time::timeout(LOGIN_GRACE, async {
+            loop {
+                if let Some((version, len)) = transport::parse_version(transport.buffer())? {
+                    transport.take_buffer(len);
+                    return Ok::<_, io::Error>(version);
+                }
+                match reader.read(&mut chunk).await? {
+                    0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                    n => transport.receive(&chunk[..n]),
+                }
+            }
+        })
+        .await
+        .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))??;
+
+        let mut connection = Connection {
+            writer,
+            transport,
+            settings,
+            host_key,
+            client_version,
+            session_id: None,
+            key_exchange: None,
+            server_kexinit: None,
+            authenticated: false,
+            channel: None,
+            output: None,
+            idle_deadline: Instant::now() + IDLE_TIMEOUT,
+        };
+        connection.send_kexinit().await?;
+
+        let login_deadline = Instant::now() + LOGIN_GRACE;
+        loop {
+            let step = tokio::select! {
+                read = reader.read(&mut chunk) => Step::Read(read?),
+                output = recv_output(&mut connection.output) => Step::Output(output),
+                _ = tokio::time::sleep_until(login_deadline), if connection.output.is_none() => {
+                    return connection
+                        .disconnect(DISCONNECT_BY_APPLICATION, "took too long to log in")
+                        .await;
+                }
+                _ = tokio::time::sleep_until(connection.idle_deadline), if connection.output.is_some() => {
+                    Step::Idle
+                }
+            };
+            match step {
+                Step::Read(0) => return Ok(()),
+                Step::Read(n) => {
+                    connection.transport.receive(&chunk[..n]);
+                    while let Some(payload) = connection.transport.next_payload()? {
+                        if !connection.handle(&payload).await? {
+                            return Ok(());
+                        }
+                    }
+                }
+                Step::Output(Some(bytes)) => {
+                    if let Some(channel) = &mut connection.channel {
+                        if channel.pending.len() + bytes.len() > MAX_PENDING_LEN {
+                            return connection
+                                .disconnect(DISCONNECT_BY_APPLICATION, "not reading output")
+                                .await;
+                        }
+                        channel.pending.extend(bytes);
+                    }
+                    connection.flush_channel().await?;
+                }
+                Step::Output(None) => {
+                    // The viewer quit: tell the client and let it hang up.
+                    connection.output = None;
+                    connection.close_channel().await?;
+                }
+                Step::Idle => {
+                    let message = format!(
+                        "Disconnected after {} minutes without input.\r\n",
+                        IDLE_TIMEOUT.as_secs() / 60
+                    );
+                    if let Some(channel) = &mut connection.channel {
+                        channel.pending.extend_from_slice(LEAVE_SCREEN);
+                        channel.pending.extend_from_slice(message.as_bytes());
+                    }
+                    connection.flush_channel().await?;
+                    return connection
+                        .disconnect(DISCONNECT_BY_APPLICATION, "idle for too long")
+                        .await;
+                }
+            }
+        }
+    }
+
+    async fn send(&mut self, payload: &[u8]) -> io::Result<()> {
+        let packet = self.transport.seal(payload);
+        self.writer.write_all(&packet).await
+    }
+
+    async fn disconnect(&mut self, reason: u32, description: &str) -> io::Result<()> {
+        let payload = Writer::new(MSG_DISCONNECT)
+            .u32(reason)
+            .string(description)
+            .string("")
+            .finish();
+        self.send(&payload).await?;
+        self.writer.shutdown().await
+    }
+
+    async fn send_kexinit(&mut self) -> io::Result<()> {
+        let kexinit = transport::kexinit();
+        self.send(&kexinit).await?;
+        self.server_kexinit = Some(kexinit);
+        Ok(())
+    }
+
+    /// Handles one message from the client. Returns false once the
+    /// connection should end.
+    async fn handle(&mut self, payload: &[u8]) -> io::Result<bool> {
+        let mut reader = Reader::new(payload);
+        let message = reader.byte()?;
+        match message {
+            MSG_DISCONNECT => return Ok(false),
+            MSG_IGNORE | MSG_DEBUG | MSG_UNIMPLEMENTED => {}
+            MSG_KEXINIT => self.handle_kexinit(payload).await?,
+            MSG_KEX_ECDH_INIT => self.handle_ecdh_init(payload).await?,
+            MSG_NEWKEYS => {
+                self.transport.new_keys_received()?;
+                self.key_exchange = None;
+                // Anything the viewer drew during the exchange can go now.
+                self.flush_channel().await?;
+            }
+            _ if self.session_id.is_none() || self.key_exchange.is_some() => {
+                return Err(protocol_error(format!(
+                    "message {} during key exchange",
+                    message
+                )));
+            }
+            MSG_SERVICE_REQUEST => {
+                let service = reader.utf8()?;
+                if service != "ssh-userauth" {
+                    self.disconnect(DISCONNECT_PROTOCOL_ERROR, "unknown service")
+                        .await?;
+                    return Ok(false);
+                }
+                let accept = Writer::new(MSG_SERVICE_ACCEPT).string(service).finish();
+                self.send(&accept).await?;
+            }
+            MSG_USERAUTH_REQUEST => {
+                // Everyone is welcome, whichever method they try first.
+                self.authenticated = true;
+                self.send(&[MSG_USERAUTH_SUCCESS]).await?;
+            }
+            _ if !self.authenticated => {
+                self.disconnect(DISCONNECT_PROTOCOL_ERROR, "not authenticated")
+                    .await?;
+                return Ok(false);
+            }
+            MSG_GLOBAL_REQUEST => {
+                reader.string()?;
+                if reader.bool()? {
+                    self.send(&[MSG_REQUEST_FAILURE]).await?;
+                }
+            }
+            MSG_CHANNEL_OPEN => self.handle_channel_open(&mut reader).await?,
+            MSG_CHANNEL_REQUEST => self.handle_channel_request(&mut reader).await?,
+            MSG_CHANNEL_WINDOW_ADJUST => {
+                reader.u32()?;
+                let bytes = reader.u32()?;
+                if let Some(channel) = &mut self.channel {
+                    channel.remote_window = channel.remote_window.saturating_add(bytes);
+                }
+                self.flush_channel().await?;
+            }
+            MSG_CHANNEL_DATA => {
+                reader.u32()?;
+                let data = reader.string()?;
+                self.handle_channel_data(data).await?;
+            }
+            MSG_CHANNEL_EOF => {
+                if let Some(channel) = &mut self.channel {
+                    channel.events = None;
+                }
+            }
+            MSG_CHANNEL_CLOSE => {
+                self.disconnect(DISCONNECT_BY_APPLICATION, "goodbye")
+                    .await?;
+                return Ok(false);
+            }
+            _ => {
+                let unimplemented = Writer::new(MSG_UNIMPLEMENTED)
+                    .u32(self.transport.last_incoming_sequence())
+                    .finish();
+                self.send(&unimplemented).await?;
+            }
+        }
+        Ok(true)
+    }
+
+    async fn handle_kexinit(&mut self, client: &[u8]) -> io::Result<()> {
+        if let Err(err) = transport::negotiate(client) {
+            self.disconnect(DISCONNECT_KEY_EXCHANGE_FAILED, &err.to_string())
+                .await?;
+            return Err(err);
+        }
+        // The client may start a fresh exchange later, which we answer.
+        let server = match self.server_kexinit.take() {
+            Some(server) => server,
+            None => {
+                self.send_kexinit().await?;
+                self.server_kexinit.take().unwrap_or_default()
+            }
+        };
+        self.key_exchange = Some(KeyExchange::AwaitingInit {
+            client: client.to_vec(),
+            server,
+        });
+        Ok(())
+    }
+
+    async fn handle_ecdh_init(&mut self, payload: &[u8]) -> io::Result<()> {
+        let Some(KeyExchange::AwaitingInit { client, server }) = self.key_exchange.take() else {
+            return Err(protocol_error("unexpected KEX_ECDH_INIT"));
+        };
+        let handshake = Handshake {
+            client_version: &self.client_version,
+            client_kexinit: &client,
+            server_kexinit: &server,
+        };
+        let (reply, exchange_hash) = transport::key_exchange(
+            &mut self.transport,
+            &handshake,
+            payload,
+            &self.host_key,
+            self.session_id.as_deref(),
+        )?;
+        self.session_id.get_or_insert(exchange_hash);
+
+        self.send(&reply).await?;
+        let new_keys = self.transport.seal_new_keys()?;
+        self.writer.write_all(&new_keys).await?;
+        self.key_exchange = Some(KeyExchange::AwaitingNewKeys);
+        Ok(())
+    }
+
+    async fn handle_channel_open(&mut self, reader: &mut Reader<'_>) -> io::Result<()> {
+        let kind = reader.utf8()?;
+        let remote_id = reader.u32()?;
+        let remote_window = reader.u32()?;
+        let remote_max_packet = reader.u32()?;
+
+        let refusal = match (kind, &self.channel) {
+            ("session", None) => None,
+            ("session", Some(_)) => Some((
+                OPEN_ADMINISTRATIVELY_PROHIBITED,
+                "one session per connection",
+            )),
+            _ => Some((OPEN_UNKNOWN_CHANNEL_TYPE, "only sessions are supported")),
+        };
+        if let Some((reason, description)) = refusal {
+            let failure = Writer::new(MSG_CHANNEL_OPEN_FAILURE)
+                .u32(remote_id)
+                .u32(reason)
+                .string(description)
+                .string("")
+                .finish();
+            return self.send(&failure).await;
+        }
+
+        self.channel = Some(Channel {
+            remote_id,
+            remote_window,
+            remote_max_packet,
+            local_window: WINDOW_SIZE,
+            pty: None,
+            events: None,
+            pending: Vec::new(),
+            closing: false,
+        });
+        let confirmation = Writer::new(MSG_CHANNEL_OPEN_CONFIRMATION)
+            .u32(remote_id)
+            .u32(0)
+            .u32(WINDOW_SIZE)
+            .u32(MAX_DATA_LEN)
+            .finish();
+        self.send(&confirmation).await
+    }
+
+    async fn handle_channel_request(&mut self, reader: &mut Reader<'_>) -> io::Result<()> {
+        reader.u32()?;
+        let kind = reader.utf8()?;
+        let want_reply = reader.bool()?;
+        let Some(channel) = &mut self.channel else {
+            return Err(protocol_error("request for a channel that is not open"));
+        };
+        let remote_id = channel.remote_id;
+        let mut close = false;
+
+        let succeeded = match kind {
+            "pty-req" => {
+                reader.utf8()?;
+                let width = reader.u32()?.clamp(1, u16::MAX as u32) as u16;
+                let height = reader.u32()?.clamp(1, u16::MAX as u32) as u16;
+                channel.pty = Some((width, height));
+                true
+            }
+            "window-change" => {
+                let width = reader.u32()?.clamp(1, u16::MAX as u32) as u16;
+                let height = reader.u32()?.clamp(1, u16::MAX as u32) as u16;
+                if let Some(events) = &channel.events {
+                    let _ = events.send(SessionEvent::Resize { width, height });
+                }
+                true
+            }
+            "shell" if channel.events.is_none() && self.output.is_none() => match channel.pty {
+                Some((width, height)) => {
+                    let (events, session_events) = mpsc::unbounded_channel();
+                    let (output, session_output) = mpsc::unbounded_channel();
+                    channel.events = Some(events);
+                    self.output = Some(session_output);
+                    tokio::spawn(super::run(
+                        self.settings.clone(),
+                        width,
+                        height,
+                        session_events,
+                        output,
+                    ));
+                    true
+                }
+                None => {
+                    channel
+                        .pending
+                        .extend_from_slice(b"The resume needs a terminal, try ssh -t\r\n");
+                    close = true;
+                    true
+                }
+            },
+            _ => false,
+        };
+
+        if want_reply {
+            let reply = match succeeded {
+                true => MSG_CHANNEL_SUCCESS,
+                false => MSG_CHANNEL_FAILURE,
+            };
+            self.send(&Writer::new(reply).u32(remote_id).finish())
+                .await?;
+        }
+        if close {
+            self.close_channel().await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_channel_data(&mut self, data: &[u8]) -> io::Result<()> {
+        self.idle_deadline = Instant::now() + IDLE_TIMEOUT;
+        let Some(channel) = &mut self.channel else {
+            return Ok(());
+        };
+        if let Some(events) = &channel.events {
+            let _ = events.send(SessionEvent::Input(data.to_vec()));
+        }
+
+        channel.local_window = channel.local_window.saturating_sub(data.len() as u32);
+        if channel.local_window < WINDOW_SIZE / 2 {
+            let adjust = Writer::new(MSG_CHANNEL_WINDOW_ADJUST)
+                .u32(channel.remote_id)
+                .u32(WINDOW_SIZE - channel.local_window)
+                .finish();
+            channel.local_window = WINDOW_SIZE;
+            self.send(&adjust).await?;
+        }
+        Ok(())
+    }
+
+    /// Sends as much pending output as the client's window allows, then
+    /// closes the channel if it is closing and nothing is left to send.
+    async fn flush_channel(&mut self) -> io::Result<()> {
+        if self.key_exchange.is_some() {
+            return Ok(());
+        }
+        while let Some(channel) = &mut self.channel {
+            let len = (channel.pending.len() as u32)
+                .min(channel.remote_window)
+                .min(channel.remote_max_packet)
+                .min(MAX_DATA_LEN) as usize;
+            if len == 0 {
+                break;
+            }
+            channel.remote_window -= len as u32;
+            let data: Vec<u8> = channel.pending.drain(..len).collect();
+            let payload = Writer::new(MSG_CHANNEL_DATA)
+                .u32(channel.remote_id)
+                .string(data)
+                .finish();
+            self.send(&payload).await?;
+        }
+
+        match &mut self.channel {
+            Some(channel) if channel.closing && channel.pending.is_empty() => {
+                channel.closing = false;
+                let remote_id = channel.remote_id;
+                self.send_close(remote_id).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Stops the viewer's input and closes the channel once the output
+    /// still waiting on the client's window has gone, leaving its terminal
+    /// as the session found it.
+    async fn close_channel(&mut self) -> io::Result<()> {
+        if let Some(channel) = &mut self.channel {
+            channel.events = None;
+            channel.closing = true;
+        }
+        self.flush_channel().await
+    }
+
+    async fn send_close(&mut self, remote_id: u32) -> io::Result<()> {
+        let exit_status = Writer::new(MSG_CHANNEL_REQUEST)
+            .u32(remote_id)
+            .string("exit-status")
+            .bool(false)
+            .u32(0)
+            .finish();
+        self.send(&exit_status).await?;
+        self.send(&Writer::new(MSG_CHANNEL_EOF).u32(remote_id).finish())
+            .await?;
+        self.send(&Writer::new(MSG_CHANNEL_CLOSE).u32(remote_id).finish())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Stdio;
+
+    use tokio::process::Command;
+
+    use super::*;
+    use crate::settings::DataSource;
+
+    /// The payloads that arrive on `stream` before it goes quiet.
+    async fn received(stream: &mut TcpStream, transport: &mut Transport) -> Vec<Vec<u8>> {
+        let mut buf = [0; 1024];
+        while let Ok(Ok(n)) =
+            tokio::time::timeout(Duration::from_millis(200), stream.read(&mut buf)).await
+        {
+            if n == 0 {
+                break;
+            }
+            transport.receive(&buf[..n]);
+        }
+        let mut payloads = Vec::new();
+        while let Some(payload) = transport.next_payload().unwrap() {
+            payloads.push(payload);
+        }
+        payloads
+    }
+
+    #[tokio::test]
+    async fn test_channel_closes_after_pending_output() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (stream, _) = listener.accept().await.unwrap();
+        let (_, writer) = stream.into_split();
+        let mut connection = Connection {
+            writer,
+            transport: Transport::default(),
+            settings: Arc::new(Settings::default()),
+            host_key: Arc::new(SigningKey::from_bytes(&[7; 32])),
+            client_version: String::new(),
+            session_id: None,
+            key_exchange: None,
+            server_kexinit: None,
+            authenticated: true,
+            channel: Some(Channel {
+                remote_id: 3,
+                remote_window: 4,
+                remote_max_packet: MAX_DATA_LEN,
+                local_window: WINDOW_SIZE,
+                pty: None,
+                events: None,
+                pending: b"bye\x1b[?1049l".to_vec(),
+                closing: false,
+            }),
+            output: None,
+            idle_deadline: Instant::now() + IDLE_TIMEOUT,
+        };
+        let mut transport = Transport::default();
+        let kinds = |payloads: &[Vec<u8>]| payloads.iter().map(|p| p[0]).collect::<Vec<_>>();
+
+        // Only what fits the window goes, and the channel stays open.
+        connection.close_channel().await.unwrap();
+        let payloads = received(&mut client, &mut transport).await;
+        assert_eq!(kinds(&payloads), [MSG_CHANNEL_DATA]);
+
+        // Nor does it close in the middle of a key exchange.
+        connection.key_exchange = Some(KeyExchange::AwaitingNewKeys);
+        if let Some(channel) = &mut connection.channel {
+            channel.remote_window = 100;
+        }
+        connection.flush_channel().await.unwrap();
+        assert!(received(&mut client, &mut transport).await.is_empty());
+
+        connection.key_exchange = None;
+        connection.flush_channel().await.unwrap();
+        let payloads = received(&mut client, &mut transport).await;
+        assert_eq!(
+            kinds(&payloads),
+            [
+                MSG_CHANNEL_DATA,
+                MSG_CHANNEL_REQUEST,
+                MSG_CHANNEL_EOF,
+                MSG_CHANNEL_CLOSE
+            ]
+        );
+        // The rest of the output, after the four bytes the window let through.
+        assert!(payloads[0].ends_with(b"[?1049l"));
+    }
+
+    /// Runs the real OpenSSH client against a connection, so the key
+    /// exchange, ciphers and MACs are checked against more than ourselves.
+    /// Skipped where `ssh` isn't installed.
+    #[tokio::test]
+    async fn test_openssh_client_interop() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let settings = Arc::new(Settings {
+            data_source: DataSource::Bundled,
+            use_cache: false,
+            ..Settings::default()
+        });
+        let host_key = Arc::new(SigningKey::from_bytes(&[7; 32]));
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            Connection::run(stream, settings, host_key).await
+        });
+
+        let client = Command::new("ssh")
+            .args(["-tt", "-p", &port.to_string()])
+            .args(["-o", "BatchMode=yes", "-o", "LogLevel=ERROR"])
+            .args(["-o", "StrictHostKeyChecking=no"])
+            .args(["-o", "UserKnownHostsFile=/dev/null"])
+            .arg("viewer@127.0.0.1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn();
+        let mut client = match client {
+            Ok(client) => client,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return,
+            Err(err) => panic!("cannot run ssh: {}", err),
+        };
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let mut stdin = client.stdin.take().unwrap();
+        stdin.write_all(b"q").await.unwrap();
+        let output = tokio::time::timeout(Duration::from_secs(10), client.wait_with_output())
+            .await
+            .expect("ssh did not exit")
+            .unwrap();
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        // The TUI switched to the alternate screen and back again.
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("\x1b[?1049h"), "{:?}", stdout);
+        assert!(stdout.contains("\x1b[?1049l"), "{:?}", stdout);
+        server.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,418 @@
+use std::io;
+
+use aes::{
+    cipher::{KeyIvInit, StreamCipher},
+    Aes128,
+};
+use ed25519_dalek::{Signer, SigningKey};
+use hmac::{Hmac, Mac};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use x25519_dalek::{EphemeralSecret, PublicKey};
+
+use super::wire::{protocol_error, Reader, Writer, MSG_KEXINIT, MSG_KEX_ECDH_REPLY, MSG_NEWKEYS};
+
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+type HmacSha256 = Hmac<Sha256>;
+
+pub const SERVER_VERSION: &str = concat!("SSH-2.0-resume_rs_", env!("CARGO_PKG_VERSION"));
+
+const KEX_ALGORITHMS: [&str; 2] = ["curve25519-sha256", "curve25519-sha256@libssh.org"];
+const HOST_KEY_ALGORITHM: &str = "ssh-ed25519";
+const CIPHER: &str = "aes128-ctr";
+const MAC: &str = "hmac-sha2-256";
+const COMPRESSION: &str = "none";
+
+const MAC_LEN: usize = 32;
+/// Comfortably above the 35000 bytes RFC 4253 requires us to accept.
+const MAX_PACKET_LEN: usize = 256 * 1024;
+
+/// Finds the client's identification line, which may follow other lines,
+/// at the front of `buffer`. Returns it without the line ending, and how
+/// many bytes to consume.
+pub fn parse_version(buffer: &[u8]) -> io::Result<Option<(String, usize)>> {
+    let mut start = 0;
+    while let Some(end) = buffer[start..].iter().position(|&b| b == b'\n') {
+        let line = &buffer[start..start + end];
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.starts_with(b"SSH-") {
+            if !line.starts_with(b"SSH-2.0-") && !line.starts_with(b"SSH-1.99-") {
+                return Err(protocol_error("only SSH protocol 2.0 is supported"));
+            }
+            let version = String::from_utf8_lossy(line).into_owned();
+            return Ok(Some((version, start + end + 1)));
+        }
+        start += end + 1;
+    }
+    match buffer.len() > 8 * 1024 {
+        true => Err(protocol_error("no SSH identification line")),
+        false => Ok(None),
+    }
+}
+
+/// Keys for one direction of the connection, derived after a key exchange.
+struct Keys {
+    cipher: Aes128Ctr,
+    mac: HmacSha256,
+}
+
+impl Keys {
+    fn derive(
+        shared_secret: &[u8],
+        exchange_hash: &[u8],
+        session_id: &[u8],
+        letters: [u8; 3],
+    ) -> Self {
+        let derive = |letter: u8| {
+            let mut hasher = Sha256::new();
+            hasher.update(Writer::default().mpint(shared_secret).finish());
+            hasher.update(exchange_hash);
+            hasher.update([letter]);
+            hasher.update(session_id);
+            hasher.finalize()
+        };
+        let (iv, key, mac) = (derive(letters[0]), derive(letters[1]), derive(letters[2]));
+        Self {
+            cipher: Aes128Ctr::new(key[..16].into(), iv[..16].into()),
+            mac: HmacSha256::new_from_slice(&mac).expect("HMAC takes keys of any length"),
+        }
+    }
+
+    fn mac(&self, sequence: u32, packet: &[u8]) -> [u8; MAC_LEN] {
+        let mut mac = self.mac.clone();
+        mac.update(&sequence.to_be_bytes());
+        mac.update(packet);
+        mac.finalize().into_bytes().into()
+    }
+
+    /// Checks the MAC the client sent with `packet`, in constant time.
+    fn verify(&self, sequence: u32, packet: &[u8], tag: &[u8]) -> bool {
+        let mut mac = self.mac.clone();
+        mac.update(&sequence.to_be_bytes());
+        mac.update(packet);
+        mac.verify_slice(tag).is_ok()
+    }
+}
+
+/// The binary packet protocol of RFC 4253 section 6: framing, padding,
+/// encryption and MACs, in both directions.
+#[derive(Default)]
+pub struct Transport {
+    buffer: Vec<u8>,
+    /// The first block of a packet whose length is known but whose body
+    /// has not all arrived yet.
+    first_block: Option<Vec<u8>>,
+    incoming: Option<Keys>,
+    outgoing: Option<Keys>,
+    /// Keys waiting for the client's NEWKEYS before they apply.
+    next_incoming: Option<Keys>,
+    /// Keys waiting for our own NEWKEYS before they apply.
+    next_outgoing: Option<Keys>,
+    incoming_sequence: u32,
+    outgoing_sequence: u32,
+}
+
+impl Transport {
+    pub fn receive(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    pub fn take_buffer(&mut self, len: usize) -> Vec<u8> {
+        self.buffer.drain(..len).collect()
+    }
+
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    fn block_size(keys: &Option<Keys>) -> usize {
+        match keys {
+            Some(_) => 16,
+            None => 8,
+        }
+    }
+
+    /// Decodes the next complete packet's payload, if it has all arrived.
+    pub fn next_payload(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let block_size = Self::block_size(&self.incoming);
+        let mut first_block = match self.first_block.take() {
+            Some(block) => block,
+            None if self.buffer.len() < block_size => return Ok(None),
+            None => {
+                let mut block = self.take_buffer(block_size);
+                if let Some(keys) = &mut self.incoming {
+                    keys.cipher.apply_keystream(&mut block);
+                }
+                block
+            }
+        };
+
+        let len = u32::from_be_bytes([
+            first_block[0],
+            first_block[1],
+            first_block[2],
+            first_block[3],
+        ]) as usize;
+        if len > MAX_PACKET_LEN || !(len + 4).is_multiple_of(block_size) || len + 4 < block_size {
+            return Err(protocol_error("bad packet length"));
+        }
+        let mac_len = match self.incoming {
+            Some(_) => MAC_LEN,
+            None => 0,
+        };
+        let remaining = len + 4 - block_size;
+        if self.buffer.len() < remaining + mac_len {
+            self.first_block = Some(first_block);
+            return Ok(None);
+        }
+
+        let mut rest = self.take_buffer(remaining);
+        let mac = self.take_buffer(mac_len);
+        if let Some(keys) = &mut self.incoming {
+            keys.cipher.apply_keystream(&mut rest);
+        }
+        first_block.extend(rest);
+        let packet = first_block;
+        if let Some(keys) = &self.incoming {
+            if !keys.verify(self.incoming_sequence, &packet, &mac) {
+                return Err(protocol_error("bad packet MAC"));
+            }
+        }
+        self.incoming_sequence = self.incoming_sequence.wrapping_add(1);
+
+        let padding = packet[4] as usize;
+        if padding < 4 || padding + 1 > len {
+            return Err(protocol_error("bad packet padding"));
+        }
+        Ok(Some(packet[5..4 + len - padding].to_vec()))
+    }
+
+    /// Frames and encrypts `payload` for the wire.
+    pub fn seal(&mut self, payload: &[u8]) -> Vec<u8> {
+        let block_size = Self::block_size(&self.outgoing);
+        let mut padding = block_size - (5 + payload.len()) % block_size;
+        if padding < 4 {
+            padding += block_size;
+        }
+
+        let mut packet = Vec::with_capacity(5 + payload.len() + padding + MAC_LEN);
+        packet.extend_from_slice(&((1 + payload.len() + padding) as u32).to_be_bytes());
+        packet.push(padding as u8);
+        packet.extend_from_slice(payload);
+        let mut random = vec![0; padding];
+        OsRng.fill_bytes(&mut random);
+        packet.extend(random);
+
+        if let Some(keys) = &mut self.outgoing {
+            let mac = keys.mac(self.outgoing_sequence, &packet);
+            keys.cipher.apply_keystream(&mut packet);
+            packet.extend_from_slice(&mac);
+        }
+        self.outgoing_sequence = self.outgoing_sequence.wrapping_add(1);
+        packet
+    }
+
+    /// The sequence number of the packet `next_payload` last returned, for
+    /// `SSH_MSG_UNIMPLEMENTED`.
+    pub fn last_incoming_sequence(&self) -> u32 {
+        self.incoming_sequence.wrapping_sub(1)
+    }
+
+    /// Seals our NEWKEYS, after which everything we send uses the new keys.
+    pub fn seal_new_keys(&mut self) -> io::Result<Vec<u8>> {
+        let keys = self
+            .next_outgoing
+            .take()
+            .ok_or_else(|| protocol_error("no key exchange in progress"))?;
+        let packet = self.seal(&[MSG_NEWKEYS]);
+        self.outgoing = Some(keys);
+        Ok(packet)
+    }
+
+    /// Switches to the new incoming keys on the client's NEWKEYS.
+    pub fn new_keys_received(&mut self) -> io::Result<()> {
+        match self.next_incoming.take() {
+            Some(keys) => {
+                self.incoming = Some(keys);
+                Ok(())
+            }
+            None => Err(protocol_error("unexpected NEWKEYS")),
+        }
+    }
+}
+
+pub fn kexinit() -> Vec<u8> {
+    let mut cookie = [0; 16];
+    OsRng.fill_bytes(&mut cookie);
+    Writer::new(MSG_KEXINIT)
+        .raw(&cookie)
+        .name_list(&KEX_ALGORITHMS)
+        .name_list(&[HOST_KEY_ALGORITHM])
+        .name_list(&[CIPHER])
+        .name_list(&[CIPHER])
+        .name_list(&[MAC])
+        .name_list(&[MAC])
+        .name_list(&[COMPRESSION])
+        .name_list(&[COMPRESSION])
+        .name_list(&[])
+        .name_list(&[])
+        .bool(false)
+        .u32(0)
+        .finish()
+}
+
+/// Checks the client's KEXINIT offers what we need. We only support one
+/// algorithm of each kind, so there is nothing else to choose.
+pub fn negotiate(client_kexinit: &[u8]) -> io::Result<()> {
+    let mut reader = Reader::new(client_kexinit);
+    reader.byte()?;
+    reader.skip(16)?;
+    let offers = |reader: &mut Reader, supported: &[&str], kind: &str| -> io::Result<()> {
+        let offered = reader.name_list()?;
+        match offered.iter().any(|name| supported.contains(name)) {
+            true => Ok(()),
+            false => Err(protocol_error(format!(
+                "no common {} algorithm, we support {} but the client offered {}",
+                kind,
+                supported.join(","),
+                offered.join(",")
+            ))),
+        }
+    };
+    offers(&mut reader, &KEX_ALGORITHMS, "key exchange")?;
+    offers(&mut reader, &[HOST_KEY_ALGORITHM], "host key")?;
+    offers(&mut reader, &[CIPHER], "cipher")?;
+    offers(&mut reader, &[CIPHER], "cipher")?;
+    offers(&mut reader, &[MAC], "MAC")?;
+    offers(&mut reader, &[MAC], "MAC")?;
+    offers(&mut reader, &[COMPRESSION], "compression")?;
+    offers(&mut reader, &[COMPRESSION], "compression")?;
+    Ok(())
+}
+
+pub fn host_key_blob(host_key: &SigningKey) -> Vec<u8> {
+    Writer::default()
+        .string(HOST_KEY_ALGORITHM)
+        .string(host_key.verifying_key().as_bytes())
+        .finish()
+}
+
+/// What the two sides have said to each other so far, all of which goes
+/// into the exchange hash.
+pub struct Handshake<'a> {
+    pub client_version: &'a str,
+    pub client_kexinit: &'a [u8],
+    pub server_kexinit: &'a [u8],
+}
+
+/// Answers the client's `SSH_MSG_KEX_ECDH_INIT` (RFC 8731), installing the
+/// new outgoing keys and staging the incoming ones. Returns the reply to
+/// send before our NEWKEYS, and the exchange hash, the first of which
+/// becomes the session id.
+pub fn key_exchange(
+    transport: &mut Transport,
+    handshake: &Handshake,
+    ecdh_init: &[u8],
+    host_key: &SigningKey,
+    session_id: Option<&[u8]>,
+) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let mut reader = Reader::new(ecdh_init);
+    reader.byte()?;
+    let client_public: [u8; 32] = reader
+        .string()?
+        .try_into()
+        .map_err(|_| protocol_error("bad curve25519 public key"))?;
+
+    let secret = EphemeralSecret::random_from_rng(OsRng);
+    let server_public = PublicKey::from(&secret);
+    let shared_secret = secret.diffie_hellman(&PublicKey::from(client_public));
+    if !shared_secret.was_contributory() {
+        return Err(protocol_error("bad curve25519 public key"));
+    }
+    let shared_secret = shared_secret.as_bytes();
+
+    let host_key_blob = host_key_blob(host_key);
+    let exchange_hash = Sha256::digest(
+        Writer::default()
+            .string(handshake.client_version)
+            .string(SERVER_VERSION)
+            .string(handshake.client_kexinit)
+            .string(handshake.server_kexinit)
+            .string(&host_key_blob)
+            .string(client_public)
+            .string(server_public.as_bytes())
+            .mpint(shared_secret)
+            .finish(),
+    )
+    .to_vec();
+
+    let signature = Writer::default()
+        .string(HOST_KEY_ALGORITHM)
+        .string(host_key.sign(&exchange_hash).to_bytes())
+        .finish();
+    let reply = Writer::new(MSG_KEX_ECDH_REPLY)
+        .string(&host_key_blob)
+        .string(server_public.as_bytes())
+        .string(signature)
+        .finish();
+
+    let session_id = session_id.unwrap_or(&exchange_hash);
+    transport.next_incoming = Some(Keys::derive(
+        shared_secret,
+        &exchange_hash,
+        session_id,
+        *b"ACE",
+    ));
+    transport.next_outgoing = Some(Keys::derive(
+        shared_secret,
+        &exchange_hash,
+        session_id,
+        *b"BDF",
+    ));
+    Ok((reply, exchange_hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version(b"SSH-2.0-Open").unwrap(), None);
+        assert_eq!(
+            parse_version(b"hello\r\nSSH-2.0-OpenSSH_9.6\r\nrest").unwrap(),
+            Some((String::from("SSH-2.0-OpenSSH_9.6"), 28))
+        );
+        assert!(parse_version(b"SSH-1.5-old\r\n").is_err());
+    }
+
+    #[test]
+    fn test_packets_round_trip_before_and_after_keys() {
+        let mut server = Transport::default();
+        let mut client = Transport::default();
+
+        let packet = server.seal(b"\x05hello");
+        assert_eq!(packet.len() % 8, 0);
+        client.receive(&packet[..3]);
+        assert_eq!(client.next_payload().unwrap(), None);
+        client.receive(&packet[3..]);
+        assert_eq!(client.next_payload().unwrap().unwrap(), b"\x05hello");
+
+        let keys = |letters| Keys::derive(b"secret", b"hash", b"hash", letters);
+        server.next_outgoing = Some(keys(*b"BDF"));
+        client.next_incoming = Some(keys(*b"BDF"));
+        client.receive(&server.seal_new_keys().unwrap());
+        assert_eq!(client.next_payload().unwrap().unwrap(), [MSG_NEWKEYS]);
+        client.new_keys_received().unwrap();
+
+        let packet = server.seal(b"\x5eencrypted");
+        assert!(!packet.windows(9).any(|window| window == b"encrypted"));
+        client.receive(&packet);
+        assert_eq!(client.next_payload().unwrap().unwrap(), b"\x5eencrypted");
+
+        let mut tampered = server.seal(b"\x5eagain");
+        tampered[6] ^= 1;
+        client.receive(&tampered);
+        assert!(client.next_payload().is_err());
+    }
+}
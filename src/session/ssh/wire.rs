@@ -0,0 +1,184 @@
+use std::io;
+
+pub const MSG_DISCONNECT: u8 = 1;
+pub const MSG_IGNORE: u8 = 2;
+pub const MSG_UNIMPLEMENTED: u8 = 3;
+pub const MSG_DEBUG: u8 = 4;
+pub const MSG_SERVICE_REQUEST: u8 = 5;
+pub const MSG_SERVICE_ACCEPT: u8 = 6;
+pub const MSG_KEXINIT: u8 = 20;
+pub const MSG_NEWKEYS: u8 = 21;
+pub const MSG_KEX_ECDH_INIT: u8 = 30;
+pub const MSG_KEX_ECDH_REPLY: u8 = 31;
+pub const MSG_USERAUTH_REQUEST: u8 = 50;
+pub const MSG_USERAUTH_SUCCESS: u8 = 52;
+pub const MSG_GLOBAL_REQUEST: u8 = 80;
+pub const MSG_REQUEST_FAILURE: u8 = 82;
+pub const MSG_CHANNEL_OPEN: u8 = 90;
+pub const MSG_CHANNEL_OPEN_CONFIRMATION: u8 = 91;
+pub const MSG_CHANNEL_OPEN_FAILURE: u8 = 92;
+pub const MSG_CHANNEL_WINDOW_ADJUST: u8 = 93;
+pub const MSG_CHANNEL_DATA: u8 = 94;
+pub const MSG_CHANNEL_EOF: u8 = 96;
+pub const MSG_CHANNEL_CLOSE: u8 = 97;
+pub const MSG_CHANNEL_REQUEST: u8 = 98;
+pub const MSG_CHANNEL_SUCCESS: u8 = 99;
+pub const MSG_CHANNEL_FAILURE: u8 = 100;
+
+pub const DISCONNECT_PROTOCOL_ERROR: u32 = 2;
+pub const DISCONNECT_KEY_EXCHANGE_FAILED: u32 = 3;
+pub const DISCONNECT_BY_APPLICATION: u32 = 11;
+pub const DISCONNECT_TOO_MANY_CONNECTIONS: u32 = 12;
+
+pub const OPEN_ADMINISTRATIVELY_PROHIBITED: u32 = 1;
+pub const OPEN_UNKNOWN_CHANNEL_TYPE: u32 = 3;
+
+pub fn protocol_error(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Reads the SSH data types of RFC 4251 section 5 off the front of a payload.
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(protocol_error("truncated message"));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    pub fn byte(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn bool(&mut self) -> io::Result<bool> {
+        Ok(self.byte()? != 0)
+    }
+
+    pub fn u32(&mut self) -> io::Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn string(&mut self) -> io::Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    pub fn utf8(&mut self) -> io::Result<&'a str> {
+        std::str::from_utf8(self.string()?).map_err(|_| protocol_error("invalid UTF-8"))
+    }
+
+    pub fn name_list(&mut self) -> io::Result<Vec<&'a str>> {
+        Ok(self
+            .utf8()?
+            .split(',')
+            .filter(|name| !name.is_empty())
+            .collect())
+    }
+
+    pub fn skip(&mut self, len: usize) -> io::Result<()> {
+        self.take(len).map(|_| ())
+    }
+}
+
+/// Builds a payload out of the same data types.
+#[derive(Default)]
+pub struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    pub fn new(message: u8) -> Self {
+        Self {
+            bytes: vec![message],
+        }
+    }
+
+    pub fn byte(mut self, value: u8) -> Self {
+        self.bytes.push(value);
+        self
+    }
+
+    pub fn raw(mut self, value: &[u8]) -> Self {
+        self.bytes.extend_from_slice(value);
+        self
+    }
+
+    pub fn bool(self, value: bool) -> Self {
+        self.byte(value as u8)
+    }
+
+    pub fn u32(self, value: u32) -> Self {
+        self.raw(&value.to_be_bytes())
+    }
+
+    pub fn string(self, value: impl AsRef<[u8]>) -> Self {
+        let value = value.as_ref();
+        self.u32(value.len() as u32).raw(value)
+    }
+
+    pub fn name_list(self, names: &[&str]) -> Self {
+        self.string(names.join(","))
+    }
+
+    /// Encodes unsigned big-endian `value` as an mpint: no leading zeros,
+    /// except one to keep the top bit clear.
+    pub fn mpint(self, value: &[u8]) -> Self {
+        let start = value.iter().position(|&b| b != 0).unwrap_or(value.len());
+        let value = &value[start..];
+        match value.first() {
+            Some(b) if b & 0x80 != 0 => self.u32(value.len() as u32 + 1).byte(0).raw(value),
+            _ => self.string(value),
+        }
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let bytes = Writer::new(MSG_CHANNEL_DATA)
+            .u32(7)
+            .string("hi")
+            .bool(true)
+            .name_list(&["a", "b"])
+            .finish();
+        let mut reader = Reader::new(&bytes);
+        assert_eq!(reader.byte().unwrap(), MSG_CHANNEL_DATA);
+        assert_eq!(reader.u32().unwrap(), 7);
+        assert_eq!(reader.string().unwrap(), b"hi");
+        assert!(reader.bool().unwrap());
+        assert_eq!(reader.name_list().unwrap(), vec!["a", "b"]);
+        assert!(reader.byte().is_err());
+    }
+
+    #[test]
+    fn test_mpint() {
+        // Examples from RFC 4251 section 5.
+        assert_eq!(Writer::default().mpint(&[]).finish(), [0, 0, 0, 0]);
+        assert_eq!(
+            Writer::default().mpint(&[0, 0x80]).finish(),
+            [0, 0, 0, 2, 0, 0x80]
+        );
+        assert_eq!(
+            Writer::default().mpint(&[0x09, 0xa3, 0x78]).finish(),
+            [0, 0, 0, 3, 0x09, 0xa3, 0x78]
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use std::{error::Error, io, net::SocketAddr, sync::Arc, time::Duration};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, Semaphore},
+    time::Instant,
+};
+
+use super::{SessionEvent, IDLE_TIMEOUT, LEAVE_SCREEN};
+use crate::settings::Settings;
+
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+
+const ECHO: u8 = 1;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+const NAWS: u8 = 31;
+const LINEMODE: u8 = 34;
+
+/// Puts the client in character mode: we echo (so it doesn't), nobody sends
+/// go-aheads, and it reports its window size rather than editing lines.
+const NEGOTIATION: [[u8; 3]; 5] = [
+    [IAC, WILL, ECHO],
+    [IAC, WILL, SUPPRESS_GO_AHEAD],
+    [IAC, DO, SUPPRESS_GO_AHEAD],
+    [IAC, DO, NAWS],
+    [IAC, DONT, LINEMODE],
+];
+
+/// How long to wait for the client's window size before drawing at 80x24.
+const SIZE_WAIT: Duration = Duration::from_secs(1);
+
+/// Far more than any option we understand needs: NAWS takes 5 bytes.
+const MAX_SUBNEGOTIATION_LEN: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Parse {
+    Data,
+    /// After a carriage return, which clients follow with NUL or LF.
+    Return,
+    Command,
+    Negotiate(u8),
+    Subnegotiation,
+    SubnegotiationCommand,
+}
+
+/// Separates what the viewer types from the telnet commands mixed in with it
+/// (RFC 854), answering option requests as it goes.
+struct Decoder {
+    parse: Parse,
+    subnegotiation: Vec<u8>,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self {
+            parse: Parse::Data,
+            subnegotiation: Vec::new(),
+        }
+    }
+}
+
+impl Decoder {
+    fn feed(&mut self, bytes: &[u8], replies: &mut Vec<u8>) -> Vec<SessionEvent> {
+        let mut events = Vec::new();
+        let mut data = Vec::new();
+        for &byte in bytes {
+            self.parse = match (self.parse, byte) {
+                (Parse::Command, IAC) => {
+                    data.push(IAC);
+                    Parse::Data
+                }
+                (Parse::Command, WILL | WONT | DO | DONT) => Parse::Negotiate(byte),
+                (Parse::Command, SB) => {
+                    self.subnegotiation.clear();
+                    Parse::Subnegotiation
+                }
+                // NOP, go-ahead, break and the rest mean nothing here.
+                (Parse::Command, _) => Parse::Data,
+                (Parse::Negotiate(command), option) => {
+                    reply(command, option, replies);
+                    Parse::Data
+                }
+                (Parse::Subnegotiation, IAC) => Parse::SubnegotiationCommand,
+                (Parse::Subnegotiation, _) => {
+                    self.push_subnegotiation(byte);
+                    Parse::Subnegotiation
+                }
+                (Parse::SubnegotiationCommand, SE) => {
+                    if let Some(resize) = self.window_size() {
+                        if !data.is_empty() {
+                            events.push(SessionEvent::Input(std::mem::take(&mut data)));
+                        }
+                        events.push(resize);
+                    }
+                    Parse::Data
+                }
+                (Parse::SubnegotiationCommand, _) => {
+                    self.push_subnegotiation(byte);
+                    Parse::Subnegotiation
+                }
+                (_, IAC) => Parse::Command,
+                (Parse::Return, b'\n' | 0) => Parse::Data,
+                (_, b'\r') => {
+                    data.push(b'\r');
+                    Parse::Return
+                }
+                (_, _) => {
+                    data.push(byte);
+                    Parse::Data
+                }
+            };
+        }
+        if !data.is_empty() {
+            events.push(SessionEvent::Input(data));
+        }
+        events
+    }
+
+    /// Keeps up to one byte past `MAX_SUBNEGOTIATION_LEN`, which is enough
+    /// to tell an oversized subnegotiation apart without buffering it.
+    fn push_subnegotiation(&mut self, byte: u8) {
+        if self.subnegotiation.len() <= MAX_SUBNEGOTIATION_LEN {
+            self.subnegotiation.push(byte);
+        }
+    }
+
+    /// The size in a NAWS subnegotiation (RFC 1073). Zero means the client
+    /// doesn't know, so it is ignored.
+    fn window_size(&self) -> Option<SessionEvent> {
+        match self.subnegotiation[..] {
+            [NAWS, w1, w2, h1, h2] => {
+                let width = u16::from_be_bytes([w1, w2]);
+                let height = u16::from_be_bytes([h1, h2]);
+                (width > 0 && height > 0).then_some(SessionEvent::Resize { width, height })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Refuses every option beyond the ones `NEGOTIATION` asked for. Refusals are
+/// never answered, so this cannot loop.
+fn reply(command: u8, option: u8, replies: &mut Vec<u8>) {
+    match (command, option) {
+        (DO, ECHO | SUPPRESS_GO_AHEAD) | (WILL, SUPPRESS_GO_AHEAD | NAWS) => {}
+        (DO, _) => replies.extend([IAC, WONT, option]),
+        (WILL, _) => replies.extend([IAC, DONT, option]),
+        _ => {}
+    }
+}
+
+/// Doubles any IAC in the output so the client doesn't take it as a command.
+fn escape(bytes: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(bytes.len());
+    for &byte in bytes {
+        if byte == IAC {
+            escaped.push(IAC);
+        }
+        escaped.push(byte);
+    }
+    escaped
+}
+
+enum Step {
+    Read(io::Result<usize>),
+    Output(Option<Vec<u8>>),
+    Idle,
+}
+
+/// Runs one viewer's session over a telnet connection until either side
+/// hangs up.
+async fn run(stream: TcpStream, settings: Arc<Settings>) -> io::Result<()> {
+    let (mut reader, mut writer) = stream.into_split();
+    writer.write_all(NEGOTIATION.as_flattened()).await?;
+
+    let mut decoder = Decoder::default();
+    let mut buf = [0; 4096];
+    let mut replies = Vec::new();
+
+    // Give the client a moment to report its size, so the first frame fits.
+    let deadline = Instant::now() + SIZE_WAIT;
+    let mut early = Vec::new();
+    loop {
+        let read = match tokio::time::timeout_at(deadline, reader.read(&mut buf)).await {
+            Ok(read) => read?,
+            Err(_) => break,
+        };
+        if read == 0 {
+            return Ok(());
+        }
+        early.extend(decoder.feed(&buf[..read], &mut replies));
+        writer.write_all(&std::mem::take(&mut replies)).await?;
+        if early
+            .iter()
+            .any(|event| matches!(event, SessionEvent::Resize { .. }))
+        {
+            break;
+        }
+    }
+    let (width, height) = early
+        .iter()
+        .rev()
+        .find_map(|event| match event {
+            SessionEvent::Resize { width, height } => Some((*width, *height)),
+            SessionEvent::Input(_) => None,
+        })
+        .unwrap_or((80, 24));
+
+    let (events, session_events) = mpsc::unbounded_channel();
+    let (output, mut session_output) = mpsc::unbounded_channel();
+    tokio::spawn(super::run(settings, width, height, session_events, output));
+    for event in early {
+        let _ = events.send(event);
+    }
+
+    let mut idle_deadline = Instant::now() + IDLE_TIMEOUT;
+    loop {
+        let step = tokio::select! {
+            read = reader.read(&mut buf) => Step::Read(read),
+            output = session_output.recv() => Step::Output(output),
+            _ = tokio::time::sleep_until(idle_deadline) => Step::Idle,
+        };
+        match step {
+            Step::Read(Ok(0)) => return Ok(()),
+            Step::Read(Ok(read)) => {
+                idle_deadline = Instant::now() + IDLE_TIMEOUT;
+                for event in decoder.feed(&buf[..read], &mut replies) {
+                    let _ = events.send(event);
+                }
+                writer.write_all(&std::mem::take(&mut replies)).await?;
+            }
+            Step::Read(Err(err)) => return Err(err),
+            Step::Output(Some(bytes)) => writer.write_all(&escape(&bytes)).await?,
+            Step::Output(None) => return writer.shutdown().await,
+            Step::Idle => {
+                let message = format!(
+                    "Disconnected after {} minutes without input.\r\n",
+                    IDLE_TIMEOUT.as_secs() / 60
+                );
+                writer.write_all(LEAVE_SCREEN).await?;
+                writer.write_all(message.as_bytes()).await?;
+                return writer.shutdown().await;
+            }
+        }
+    }
+}
+
+/// Serves an independent copy of the TUI to each telnet client, for sharing
+/// the resume with no client setup at all.
+pub async fn serve(
+    settings: Settings,
+    addr: SocketAddr,
+    max_connections: usize,
+) -> Result<(), Box<dyn Error>> {
+    let settings = Arc::new(settings);
+    let slots = Arc::new(Semaphore::new(max_connections));
+    let listener = TcpListener::bind(addr).await?;
+    eprintln!(
+        "serving the resume over telnet on {}",
+        listener.local_addr()?
+    );
+
+    loop {
+        let (mut stream, peer) = listener.accept().await?;
+        let settings = settings.clone();
+        match slots.clone().try_acquire_owned() {
+            Ok(slot) => {
+                tokio::spawn(async move {
+                    if let Err(err) = run(stream, settings).await {
+                        eprintln!("telnet {}: {}", peer, err);
+                    }
+                    drop(slot);
+                });
+            }
+            Err(_) => {
+                tokio::spawn(async move {
+                    let _ = stream
+                        .write_all(b"Too many viewers right now, please try again later\r\n")
+                        .await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decoder() {
+        let mut decoder = Decoder::default();
+        let mut replies = Vec::new();
+        let events = decoder.feed(
+            &[
+                IAC, WILL, NAWS, IAC, SB, NAWS, 0, 100, 0, 30, IAC, SE, b'j', b'\r', 0, IAC, IAC,
+                IAC, DO, 24, b'\r', b'\n', IAC, WILL, 24,
+            ],
+            &mut replies,
+        );
+        assert_eq!(
+            events,
+            vec![
+                SessionEvent::Resize {
+                    width: 100,
+                    height: 30
+                },
+                SessionEvent::Input(vec![b'j', b'\r', IAC, b'\r']),
+            ]
+        );
+        assert_eq!(replies, [IAC, WONT, 24, IAC, DONT, 24]);
+    }
+
+    #[test]
+    fn test_split_subnegotiation() {
+        let mut decoder = Decoder::default();
+        let mut replies = Vec::new();
+        assert!(decoder
+            .feed(&[IAC, SB, NAWS, 0, 120, 0], &mut replies)
+            .is_empty());
+        assert_eq!(
+            decoder.feed(&[40, IAC, SE, b'q'], &mut replies),
+            vec![
+                SessionEvent::Resize {
+                    width: 120,
+                    height: 40
+                },
+                SessionEvent::Input(b"q".to_vec()),
+            ]
+        );
+        assert_eq!(escape(&[b'a', IAC]), [b'a', IAC, IAC]);
+    }
+
+    #[test]
+    fn test_oversized_subnegotiation_is_discarded() {
+        let mut decoder = Decoder::default();
+        let mut replies = Vec::new();
+        assert!(decoder.feed(&[IAC, SB, NAWS], &mut replies).is_empty());
+        for _ in 0..1000 {
+            assert!(decoder.feed(&[0; 100], &mut replies).is_empty());
+            assert!(decoder.subnegotiation.len() <= MAX_SUBNEGOTIATION_LEN + 1);
+        }
+        assert_eq!(
+            decoder.feed(&[0, 80, 0, 24, IAC, SE, b'q'], &mut replies),
+            vec![SessionEvent::Input(b"q".to_vec())]
+        );
+    }
+}
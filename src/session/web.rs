@@ -0,0 +1,234 @@
+use std::{error::Error, net::SocketAddr, sync::Arc};
+
+use axum::{
+    extract::{
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{
+        header::{HOST, ORIGIN},
+        HeaderMap, StatusCode,
+    },
+    response::{Html, IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use tokio::{
+    sync::{mpsc, OwnedSemaphorePermit, Semaphore},
+    time::Instant,
+};
+
+use super::{SessionEvent, IDLE_TIMEOUT};
+use crate::settings::Settings;
+
+const PAGE: &str = include_str!("web.html");
+
+#[derive(Clone)]
+struct Web {
+    settings: Arc<Settings>,
+    slots: Arc<Semaphore>,
+}
+
+/// The terminal size the page measured before connecting.
+#[derive(Deserialize)]
+struct Size {
+    cols: u16,
+    rows: u16,
+}
+
+/// Control messages from the page. Keystrokes arrive as binary messages.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Control {
+    Resize { cols: u16, rows: u16 },
+}
+
+fn session_event(message: Message) -> Option<SessionEvent> {
+    match message {
+        Message::Binary(bytes) => Some(SessionEvent::Input(bytes.to_vec())),
+        Message::Text(text) => match serde_json::from_str(&text).ok()? {
+            Control::Resize { cols, rows } => Some(SessionEvent::Resize {
+                width: cols.max(1),
+                height: rows.max(1),
+            }),
+        },
+        _ => None,
+    }
+}
+
+async fn page() -> Html<&'static str> {
+    Html(PAGE)
+}
+
+/// Whether the page that opened the socket came from this server. Browsers
+/// always send `Origin` with a WebSocket handshake, and without this check
+/// any other site could open sessions from its visitors' tabs.
+fn same_origin(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(ORIGIN) else {
+        // Not a browser, so not a page on some other site either.
+        return true;
+    };
+    let origin_host = origin
+        .to_str()
+        .ok()
+        .and_then(|origin| origin.split_once("://"))
+        .map(|(_, host)| host);
+    let host = headers.get(HOST).and_then(|host| host.to_str().ok());
+    matches!((origin_host, host), (Some(origin), Some(host)) if origin.eq_ignore_ascii_case(host))
+}
+
+async fn socket(
+    ws: WebSocketUpgrade,
+    headers: HeaderMap,
+    Query(size): Query<Size>,
+    State(web): State<Web>,
+) -> Response {
+    if !same_origin(&headers) {
+        return (
+            StatusCode::FORBIDDEN,
+            "Cross-origin connections are refused",
+        )
+            .into_response();
+    }
+    match web.slots.clone().try_acquire_owned() {
+        Ok(slot) => ws.on_upgrade(move |socket| run(socket, web.settings, size, slot)),
+        Err(_) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Too many viewers right now, please try again later",
+        )
+            .into_response(),
+    }
+}
+
+enum Step {
+    Received(Option<Message>),
+    Output(Option<Vec<u8>>),
+    Idle,
+}
+
+/// Bridges one browser tab to its own session until either side hangs up.
+async fn run(
+    mut socket: WebSocket,
+    settings: Arc<Settings>,
+    size: Size,
+    _slot: OwnedSemaphorePermit,
+) {
+    let (events, session_events) = mpsc::unbounded_channel();
+    let (output, mut session_output) = mpsc::unbounded_channel();
+    let (width, height) = (size.cols.max(1), size.rows.max(1));
+    tokio::spawn(super::run(settings, width, height, session_events, output));
+
+    let mut idle_deadline = Instant::now() + IDLE_TIMEOUT;
+    loop {
+        let step = tokio::select! {
+            message = socket.recv() => Step::Received(message.and_then(Result::ok)),
+            output = session_output.recv() => Step::Output(output),
+            _ = tokio::time::sleep_until(idle_deadline) => Step::Idle,
+        };
+        match step {
+            Step::Received(None) | Step::Received(Some(Message::Close(_))) => return,
+            Step::Received(Some(message)) => {
+                idle_deadline = Instant::now() + IDLE_TIMEOUT;
+                if let Some(event) = session_event(message) {
+                    let _ = events.send(event);
+                }
+            }
+            Step::Output(Some(bytes)) => {
+                if socket.send(Message::Binary(bytes.into())).await.is_err() {
+                    return;
+                }
+            }
+            Step::Output(None) => {
+                let close = CloseFrame {
+                    code: 1000,
+                    reason: "Thanks for visiting".into(),
+                };
+                let _ = socket.send(Message::Close(Some(close))).await;
+                return;
+            }
+            Step::Idle => {
+                let close = CloseFrame {
+                    code: 1000,
+                    reason: format!(
+                        "Closed after {} minutes without input",
+                        IDLE_TIMEOUT.as_secs() / 60
+                    )
+                    .into(),
+                };
+                let _ = socket.send(Message::Close(Some(close))).await;
+                return;
+            }
+        }
+    }
+}
+
+/// Serves a page with a terminal emulator that runs an independent copy of
+/// the TUI for each tab, over a WebSocket.
+pub async fn serve(
+    settings: Settings,
+    addr: SocketAddr,
+    max_connections: usize,
+) -> Result<(), Box<dyn Error>> {
+    let web = Web {
+        settings: Arc::new(settings),
+        slots: Arc::new(Semaphore::new(max_connections)),
+    };
+    let app = Router::new()
+        .route("/", get(page))
+        .route("/ws", get(socket))
+        .with_state(web);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!(
+        "serving the resume to browsers on http://{}",
+        listener.local_addr()?
+    );
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_events_from_messages() {
+        assert_eq!(
+            session_event(Message::Binary(b"q".to_vec().into())),
+            Some(SessionEvent::Input(b"q".to_vec()))
+        );
+        assert_eq!(
+            session_event(Message::Text(
+                r#"{"type":"resize","cols":90,"rows":0}"#.into()
+            )),
+            Some(SessionEvent::Resize {
+                width: 90,
+                height: 1
+            })
+        );
+        assert_eq!(session_event(Message::Text("nonsense".into())), None);
+    }
+
+    #[test]
+    fn test_same_origin() {
+        let headers = |pairs: &[(axum::http::HeaderName, &str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.insert(name.clone(), value.parse().unwrap());
+            }
+            headers
+        };
+
+        assert!(same_origin(&headers(&[(HOST, "localhost:8080")])));
+        assert!(same_origin(&headers(&[
+            (HOST, "localhost:8080"),
+            (ORIGIN, "http://localhost:8080")
+        ])));
+        assert!(!same_origin(&headers(&[
+            (HOST, "localhost:8080"),
+            (ORIGIN, "https://evil.example")
+        ])));
+        assert!(!same_origin(&headers(&[(ORIGIN, "null")])));
+    }
+}
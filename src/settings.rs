@@ -1,6 +1,91 @@
+use std::{collections::BTreeMap, env, fmt, path::PathBuf, str::FromStr};
+
+use crate::{
+    config::Config,
+    resume::DocumentFormat,
+    ui::{tabs::TabsHeadings, theme::Theme},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataSource {
+    Local(PathBuf),
+    S3,
+    Http(String),
+    Bundled,
+}
+
+impl FromStr for DataSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Err(String::from("data source must not be empty")),
+            "s3" => Ok(Self::S3),
+            "bundled" => Ok(Self::Bundled),
+            url if url.starts_with("http://") || url.starts_with("https://") => {
+                Ok(Self::Http(url.to_string()))
+            }
+            dir => Ok(Self::Local(PathBuf::from(dir))),
+        }
+    }
+}
+
+impl fmt::Display for DataSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local(dir) => write!(f, "{}", dir.display()),
+            Self::S3 => write!(f, "s3"),
+            Self::Http(url) => write!(f, "{}", url),
+            Self::Bundled => write!(f, "bundled"),
+        }
+    }
+}
+
+fn non_empty(value: String) -> Option<String> {
+    match value.is_empty() {
+        true => None,
+        false => Some(value),
+    }
+}
+
+/// Expands a leading `~` to `$HOME`, as a shell would, for paths written in
+/// the config file.
+fn expand_home(path: String) -> PathBuf {
+    let rest = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest.trim_start_matches('/'),
+        _ => return PathBuf::from(path),
+    };
+    match env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
 pub struct Settings {
     pub poll_duration_ms: u64,
     pub aws_bucket: String,
+    pub aws_region: String,
+    /// Talk to an S3-compatible store such as MinIO or LocalStack instead of AWS.
+    pub aws_endpoint_url: Option<String>,
+    /// Address buckets as `<endpoint>/<bucket>` rather than `<bucket>.<endpoint>`,
+    /// which most local S3-compatible stores require.
+    pub aws_force_path_style: bool,
+    pub aws_key_prefix: String,
+    pub aws_profile: Option<String>,
+    pub data_source: DataSource,
+    /// How to read a `data_source` that is a single file, or `None` to go
+    /// by its `$schema`.
+    pub document_format: Option<DocumentFormat>,
+    pub use_cache: bool,
+    /// Reload sections when their files change. Only for local data directories.
+    pub watch: bool,
+    pub theme: Theme,
+    pub initial_tab: TabsHeadings,
+    /// Searched for `<name>.tex` before the built-in LaTeX templates.
+    pub latex_template_dir: Option<PathBuf>,
+    /// Event name to keys, e.g. `next_employer = ["n", "Down"]`, or
+    /// `context.event` for one context only, plus an optional `preset`.
+    pub keybindings: BTreeMap<String, Vec<String>>,
 }
 
 impl Settings {
@@ -8,6 +93,171 @@ impl Settings {
         Self {
             poll_duration_ms: 100,
             aws_bucket: String::from("rusty-resume-s3-bucket"),
+            aws_region: String::from("eu-west-2"),
+            aws_endpoint_url: None,
+            aws_force_path_style: false,
+            aws_key_prefix: String::new(),
+            aws_profile: None,
+            data_source: DataSource::S3,
+            document_format: None,
+            use_cache: true,
+            watch: false,
+            theme: Theme::Green,
+            initial_tab: TabsHeadings::AboutMe,
+            latex_template_dir: None,
+            keybindings: BTreeMap::new(),
+        }
+    }
+
+    /// Merges each configuration layer over the built-in defaults, later
+    /// layers winning.
+    pub fn from_layers(layers: Vec<Config>) -> Result<Self, String> {
+        let mut settings = Self::default();
+        for layer in layers {
+            settings.apply(layer)?;
+        }
+        Ok(settings)
+    }
+
+    fn apply(&mut self, layer: Config) -> Result<(), String> {
+        if let Some(poll_interval_ms) = layer.poll_interval_ms {
+            self.poll_duration_ms = poll_interval_ms;
+        }
+        if let Some(source) = layer.source {
+            self.data_source = source.parse()?;
         }
+        if let Some(document_format) = layer.document_format {
+            self.document_format = non_empty(document_format)
+                .map(|format| format.parse())
+                .transpose()?;
+        }
+        if let Some(cache) = layer.cache {
+            self.use_cache = cache;
+        }
+        if let Some(watch) = layer.watch {
+            self.watch = watch;
+        }
+        if let Some(theme) = layer.theme {
+            self.theme = theme.parse()?;
+        }
+        if let Some(initial_tab) = layer.initial_tab {
+            self.initial_tab = initial_tab.parse()?;
+        }
+
+        let s3 = layer.s3;
+        if let Some(bucket) = s3.bucket {
+            self.aws_bucket = bucket;
+        }
+        if let Some(region) = s3.region {
+            self.aws_region = region;
+        }
+        if let Some(endpoint_url) = s3.endpoint_url {
+            self.aws_endpoint_url = non_empty(endpoint_url);
+        }
+        if let Some(force_path_style) = s3.force_path_style {
+            self.aws_force_path_style = force_path_style;
+        }
+        if let Some(key_prefix) = s3.key_prefix {
+            self.aws_key_prefix = key_prefix;
+        }
+        if let Some(profile) = s3.profile {
+            self.aws_profile = non_empty(profile);
+        }
+
+        if let Some(template_dir) = layer.latex.template_dir {
+            self.latex_template_dir = non_empty(template_dir).map(expand_home);
+        }
+
+        self.keybindings.extend(layer.keybindings);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::S3Config;
+
+    #[test]
+    fn test_parse_data_source() {
+        assert_eq!("s3".parse(), Ok(DataSource::S3));
+        assert_eq!("bundled".parse(), Ok(DataSource::Bundled));
+        assert_eq!(
+            "https://example.com/resume".parse(),
+            Ok(DataSource::Http(String::from("https://example.com/resume")))
+        );
+        assert_eq!(
+            "./data".parse(),
+            Ok(DataSource::Local(PathBuf::from("./data")))
+        );
+        assert!("".parse::<DataSource>().is_err());
+    }
+
+    #[test]
+    fn test_later_layers_win() {
+        let file = Config {
+            poll_interval_ms: Some(250),
+            theme: Some(String::from("amber")),
+            s3: S3Config {
+                region: Some(String::from("us-east-1")),
+                endpoint_url: Some(String::from("http://localhost:9000")),
+                ..S3Config::default()
+            },
+            keybindings: BTreeMap::from([
+                (String::from("quit"), vec![String::from("x")]),
+                (String::from("retry"), vec![String::from("R")]),
+            ]),
+            ..Config::default()
+        };
+        let env = Config {
+            theme: Some(String::from("mono")),
+            s3: S3Config {
+                endpoint_url: Some(String::new()),
+                ..S3Config::default()
+            },
+            keybindings: BTreeMap::from([(String::from("quit"), vec![String::from("Q")])]),
+            ..Config::default()
+        };
+        let cli = Config {
+            initial_tab: Some(String::from("contact")),
+            ..Config::default()
+        };
+
+        let settings = Settings::from_layers(vec![file, env, cli]).unwrap();
+        assert_eq!(settings.poll_duration_ms, 250);
+        assert_eq!(settings.theme, Theme::Mono);
+        assert_eq!(settings.initial_tab, TabsHeadings::ContactDetails);
+        assert_eq!(settings.aws_region, "us-east-1");
+        assert_eq!(settings.aws_endpoint_url, None);
+        assert_eq!(settings.aws_bucket, "rusty-resume-s3-bucket");
+        assert_eq!(settings.keybindings["quit"], ["Q"]);
+        assert_eq!(settings.keybindings["retry"], ["R"]);
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = PathBuf::from(env::var_os("HOME").unwrap());
+        assert_eq!(expand_home(String::from("~")), home);
+        assert_eq!(
+            expand_home(String::from("~/templates")),
+            home.join("templates")
+        );
+        assert_eq!(
+            expand_home(String::from("~other/templates")),
+            PathBuf::from("~other/templates")
+        );
+        assert_eq!(
+            expand_home(String::from("/srv/templates")),
+            PathBuf::from("/srv/templates")
+        );
+    }
+
+    #[test]
+    fn test_invalid_layer_is_an_error() {
+        let layer = Config {
+            theme: Some(String::from("neon")),
+            ..Config::default()
+        };
+        assert!(Settings::from_layers(vec![layer]).is_err());
     }
 }
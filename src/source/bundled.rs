@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+
+use super::ResumeSource;
+use crate::{assets::data, error::LoadError};
+
+/// Resume data compiled into the binary, for running without network or
+/// files. Documents that were not bundled fail to load like missing files.
+pub struct BundledSource;
+
+#[async_trait]
+impl ResumeSource for BundledSource {
+    async fn fetch(&self, key: &str) -> Result<Vec<u8>, LoadError> {
+        data::DOCUMENTS
+            .iter()
+            .find(|(bundled, _)| *bundled == key)
+            .map(|(_, contents)| contents.as_bytes().to_vec())
+            .ok_or_else(|| LoadError::NotBundled(key.to_string()))
+    }
+}
@@ -0,0 +1,76 @@
+use std::{io, path::PathBuf};
+
+use async_trait::async_trait;
+
+use super::{ResumeSource, ABOUT_ME_KEY, CONTACT_KEY, EDUCATION_KEY, EMPLOYMENT_KEY, PROFILE_KEY};
+use crate::{
+    error::LoadError,
+    resume::{DocumentFormat, Resume},
+};
+
+/// A whole resume kept in one JSON file, either our own format or a JSON
+/// Resume document, split into sections as they are asked for.
+pub struct DocumentSource {
+    path: PathBuf,
+    /// `None` goes by the document's `$schema`.
+    format: Option<DocumentFormat>,
+}
+
+impl DocumentSource {
+    pub fn new(path: PathBuf, format: Option<DocumentFormat>) -> Self {
+        Self { path, format }
+    }
+
+    async fn read(&self) -> Result<Resume, LoadError> {
+        let bytes = tokio::fs::read(&self.path).await.map_err(|err| {
+            LoadError::Io(io::Error::new(
+                err.kind(),
+                format!("{}: {}", self.path.display(), err),
+            ))
+        })?;
+        Resume::from_json(&bytes, self.format)
+            .map_err(|err| LoadError::malformed_json(&self.path.display().to_string(), err))
+    }
+}
+
+#[async_trait]
+impl ResumeSource for DocumentSource {
+    async fn fetch(&self, key: &str) -> Result<Vec<u8>, LoadError> {
+        let resume = self.read().await?;
+        let section = match key {
+            EMPLOYMENT_KEY => serde_json::to_vec(&resume.employment),
+            EDUCATION_KEY => serde_json::to_vec(&resume.education),
+            CONTACT_KEY => serde_json::to_vec(&resume.contact),
+            ABOUT_ME_KEY => serde_json::to_vec(&resume.about),
+            PROFILE_KEY => serde_json::to_vec(&resume.profile),
+            _ => return Err(LoadError::NotFound(key.to_string())),
+        };
+        section.map_err(|err| LoadError::Request(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_splits_json_resume_into_sections() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("resume.json");
+        std::fs::write(
+            &path,
+            r#"{"basics": {"summary": "Hi"}, "work": [{"name": "Acme", "position": "Dev"}]}"#,
+        )
+        .unwrap();
+
+        let source = DocumentSource::new(path, Some(DocumentFormat::JsonResume));
+        let about: serde_json::Value =
+            serde_json::from_slice(&source.fetch(ABOUT_ME_KEY).await.unwrap()).unwrap();
+        assert_eq!(about["about_me"], "Hi");
+
+        let employment: serde_json::Value =
+            serde_json::from_slice(&source.fetch(EMPLOYMENT_KEY).await.unwrap()).unwrap();
+        assert_eq!(employment[0]["employer"], "Acme");
+        assert_eq!(employment[0]["end_date"], "Present");
+    }
+}
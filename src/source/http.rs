@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+
+use super::ResumeSource;
+use crate::error::LoadError;
+
+pub struct HttpSource {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl HttpSource {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url, key)
+    }
+}
+
+#[async_trait]
+impl ResumeSource for HttpSource {
+    async fn fetch(&self, key: &str) -> Result<Vec<u8>, LoadError> {
+        let url = self.url(key);
+        let resp = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|err| LoadError::Request(err.to_string()))?;
+
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(LoadError::NotFound(url));
+        }
+
+        let resp = resp
+            .error_for_status()
+            .map_err(|err| LoadError::Request(err.to_string()))?;
+        let bytes = resp
+            .bytes()
+            .await
+            .map_err(|err| LoadError::Request(err.to_string()))?;
+        Ok(bytes.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_joins_base_and_key() {
+        let source = HttpSource::new("https://example.com/resume/");
+        assert_eq!(
+            source.url("employment.json"),
+            "https://example.com/resume/employment.json"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use std::{io, path::PathBuf};
+
+use async_trait::async_trait;
+
+use super::ResumeSource;
+use crate::error::LoadError;
+
+pub struct LocalSource {
+    dir: PathBuf,
+}
+
+impl LocalSource {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+#[async_trait]
+impl ResumeSource for LocalSource {
+    async fn fetch(&self, key: &str) -> Result<Vec<u8>, LoadError> {
+        let path = self.dir.join(key);
+        tokio::fs::read(&path).await.map_err(|err| {
+            LoadError::Io(io::Error::new(
+                err.kind(),
+                format!("{}: {}", path.display(), err),
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_reads_file_from_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("employment.json"), "[]").unwrap();
+
+        let source = LocalSource::new(dir.path().to_path_buf());
+        assert_eq!(source.fetch("employment.json").await.unwrap(), b"[]");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_missing_file_is_an_error() {
+        let source = LocalSource::new(PathBuf::from("/definitely/not/here"));
+        match source.fetch("employment.json").await {
+            Err(LoadError::Io(err)) => assert_eq!(err.kind(), io::ErrorKind::NotFound),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
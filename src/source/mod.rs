@@ -0,0 +1,158 @@
+use std::{str::FromStr, sync::Arc, time::SystemTime};
+
+use async_trait::async_trait;
+
+use crate::{
+    error::LoadError,
+    settings::{DataSource, Settings},
+};
+
+mod bundled;
+mod document;
+mod http;
+mod local;
+mod s3;
+
+pub use bundled::BundledSource;
+pub use document::DocumentSource;
+pub use http::HttpSource;
+pub use local::LocalSource;
+pub use s3::S3Source;
+
+pub const EMPLOYMENT_KEY: &str = "employment.json";
+pub const EDUCATION_KEY: &str = "education.json";
+pub const CONTACT_KEY: &str = "contact.json";
+pub const ABOUT_ME_KEY: &str = "about_me.json";
+pub const PROFILE_KEY: &str = "profile.json";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Section {
+    Employment,
+    Education,
+    Contact,
+    AboutMe,
+    Profile,
+}
+
+impl Section {
+    pub const ALL: [Section; 5] = [
+        Section::Profile,
+        Section::AboutMe,
+        Section::Contact,
+        Section::Employment,
+        Section::Education,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Section::Employment => "employment",
+            Section::Education => "education",
+            Section::Contact => "contact",
+            Section::AboutMe => "about",
+            Section::Profile => "profile",
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Section::Employment => EMPLOYMENT_KEY,
+            Section::Education => EDUCATION_KEY,
+            Section::Contact => CONTACT_KEY,
+            Section::AboutMe => ABOUT_ME_KEY,
+            Section::Profile => PROFILE_KEY,
+        }
+    }
+
+    /// Whether a source may leave this section out, in which case it is
+    /// treated as empty rather than as an error. Only the employment and
+    /// education history are required, as buckets set up before the other
+    /// sections existed hold nothing else.
+    pub fn is_optional(&self) -> bool {
+        matches!(self, Section::Profile | Section::AboutMe | Section::Contact)
+    }
+}
+
+impl FromStr for Section {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|section| section.name() == s)
+            .ok_or_else(|| format!("unknown section {:?}", s))
+    }
+}
+
+pub enum Fetched {
+    Modified {
+        bytes: Vec<u8>,
+        etag: Option<String>,
+        /// When the source says the document last changed, if it keeps track.
+        modified: Option<SystemTime>,
+    },
+    NotModified,
+}
+
+/// Somewhere the resume documents can be read from.
+///
+/// Every section of the resume is stored as its own JSON document and is
+/// addressed by key, e.g. [`EMPLOYMENT_KEY`].
+#[async_trait]
+pub trait ResumeSource: Send + Sync {
+    async fn fetch(&self, key: &str) -> Result<Vec<u8>, LoadError>;
+
+    /// Like [`ResumeSource::fetch`], but skips the download when the document
+    /// still matches `etag`. Sources without conditional requests always
+    /// download.
+    async fn fetch_if_changed(&self, key: &str, _etag: Option<&str>) -> Result<Fetched, LoadError> {
+        Ok(Fetched::Modified {
+            bytes: self.fetch(key).await?,
+            etag: None,
+            modified: None,
+        })
+    }
+
+    /// Where documents from this source live in the offline cache, or `None`
+    /// if they are cheap enough to read that caching is pointless.
+    fn cache_namespace(&self) -> Option<String> {
+        None
+    }
+}
+
+pub async fn from_settings(settings: &Settings) -> Arc<dyn ResumeSource> {
+    match &settings.data_source {
+        DataSource::Local(path) if path.is_file() => {
+            Arc::new(DocumentSource::new(path.clone(), settings.document_format))
+        }
+        DataSource::Local(dir) => Arc::new(LocalSource::new(dir.clone())),
+        DataSource::S3 => Arc::new(S3Source::new(settings).await),
+        DataSource::Http(base_url) => Arc::new(HttpSource::new(base_url)),
+        DataSource::Bundled => Arc::new(BundledSource),
+    }
+}
+
+/// A complete resume held in memory, for tests that need real content.
+#[cfg(test)]
+pub struct SampleSource;
+
+#[cfg(test)]
+#[async_trait]
+impl ResumeSource for SampleSource {
+    async fn fetch(&self, key: &str) -> Result<Vec<u8>, LoadError> {
+        let contents = match key {
+            PROFILE_KEY => r#"{"name": "Ada Lovelace", "label": "Analyst"}"#,
+            ABOUT_ME_KEY => r#"{"about_me": "I write programs for the Analytical Engine."}"#,
+            CONTACT_KEY => r#"{"email": "ada@example.com"}"#,
+            EMPLOYMENT_KEY => {
+                r#"[{"employer": "Analytical Engine", "role": "Programmer",
+                     "start_date": "1842", "end_date": "1843"}]"#
+            }
+            EDUCATION_KEY => {
+                r#"[{"educator": "Home tutors", "qualification": "Mathematics",
+                     "start_date": "1832", "end_date": "1840"}]"#
+            }
+            _ => return Err(LoadError::NotFound(key.to_string())),
+        };
+        Ok(contents.as_bytes().to_vec())
+    }
+}
@@ -0,0 +1,212 @@
+use std::{error::Error, time::SystemTime};
+
+use async_trait::async_trait;
+use aws_config::{BehaviorVersion, Region};
+use aws_credential_types::provider::error::CredentialsError;
+use aws_sdk_s3::{
+    error::{ProvideErrorMetadata, SdkError},
+    operation::get_object::GetObjectError,
+    Client,
+};
+
+use super::{Fetched, ResumeSource};
+use crate::{error::LoadError, settings::Settings};
+
+pub struct S3Source {
+    client: Client,
+    bucket: String,
+    key_prefix: String,
+    /// A custom endpoint such as MinIO, or `None` for AWS itself.
+    endpoint_url: Option<String>,
+}
+
+impl S3Source {
+    pub async fn new(settings: &Settings) -> Self {
+        let mut config_loader = aws_config::defaults(BehaviorVersion::latest())
+            .region(Region::new(settings.aws_region.clone()));
+        if let Some(profile) = &settings.aws_profile {
+            config_loader = config_loader.profile_name(profile);
+        }
+        if let Some(endpoint_url) = &settings.aws_endpoint_url {
+            config_loader = config_loader.endpoint_url(endpoint_url);
+        }
+        let config = config_loader.load().await;
+
+        let s3_config = aws_sdk_s3::config::Builder::from(&config)
+            .force_path_style(settings.aws_force_path_style)
+            .build();
+
+        Self {
+            endpoint_url: settings.aws_endpoint_url.clone(),
+            ..Self::with_client(
+                Client::from_conf(s3_config),
+                &settings.aws_bucket,
+                &settings.aws_key_prefix,
+            )
+        }
+    }
+
+    pub fn with_client(client: Client, bucket: &str, key_prefix: &str) -> Self {
+        let key_prefix = match key_prefix.trim_matches('/') {
+            "" => String::new(),
+            prefix => format!("{}/", prefix),
+        };
+
+        Self {
+            client,
+            bucket: bucket.to_string(),
+            key_prefix,
+            endpoint_url: None,
+        }
+    }
+
+    fn object_key(&self, key: &str) -> String {
+        format!("{}{}", self.key_prefix, key)
+    }
+
+    fn classify_error(&self, key: &str, err: SdkError<GetObjectError>) -> LoadError {
+        if let Some(service_err) = err.as_service_error() {
+            if service_err.is_no_such_key() {
+                return LoadError::NotFound(format!(
+                    "s3://{}/{}",
+                    self.bucket,
+                    self.object_key(key)
+                ));
+            }
+            if service_err.code() == Some("NoSuchBucket") {
+                return LoadError::BucketNotFound(self.bucket.clone());
+            }
+        }
+
+        let mut cause: Option<&(dyn Error + 'static)> = Some(&err);
+        while let Some(inner) = cause {
+            if inner.is::<CredentialsError>() {
+                return LoadError::MissingCredentials;
+            }
+            cause = inner.source();
+        }
+
+        LoadError::Request(format!("{}", aws_sdk_s3::error::DisplayErrorContext(&err)))
+    }
+}
+
+#[async_trait]
+impl ResumeSource for S3Source {
+    async fn fetch(&self, key: &str) -> Result<Vec<u8>, LoadError> {
+        match self.fetch_if_changed(key, None).await? {
+            Fetched::Modified { bytes, .. } => Ok(bytes),
+            Fetched::NotModified => Err(LoadError::Request(format!(
+                "s3://{}/{} answered an unconditional request with 304",
+                self.bucket,
+                self.object_key(key)
+            ))),
+        }
+    }
+
+    async fn fetch_if_changed(&self, key: &str, etag: Option<&str>) -> Result<Fetched, LoadError> {
+        let result = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(key))
+            .set_if_none_match(etag.map(String::from))
+            .send()
+            .await;
+
+        let resp = match result {
+            Ok(resp) => resp,
+            Err(err) if err.raw_response().map(|r| r.status().as_u16()) == Some(304) => {
+                return Ok(Fetched::NotModified)
+            }
+            Err(err) => return Err(self.classify_error(key, err)),
+        };
+
+        let etag = resp.e_tag().map(String::from);
+        let modified = resp
+            .last_modified()
+            .and_then(|date| SystemTime::try_from(*date).ok());
+        let data = resp
+            .body
+            .collect()
+            .await
+            .map_err(|err| LoadError::Request(err.to_string()))?;
+        Ok(Fetched::Modified {
+            bytes: data.into_bytes().to_vec(),
+            etag,
+            modified,
+        })
+    }
+
+    /// Buckets are only unique per endpoint and region, so both are part of
+    /// the namespace, made safe to use as directory names.
+    fn cache_namespace(&self) -> Option<String> {
+        let endpoint = match &self.endpoint_url {
+            Some(url) => url
+                .chars()
+                .map(
+                    |c| match c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                        true => c,
+                        false => '_',
+                    },
+                )
+                .collect(),
+            None => String::from("aws"),
+        };
+        let config = self.client.config();
+        let region = config
+            .region()
+            .map_or("no-region", |region| region.as_ref());
+        Some(format!(
+            "{}/{}/{}/{}",
+            endpoint, region, self.bucket, self.key_prefix
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(key_prefix: &str) -> S3Source {
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("eu-west-2"))
+            .build();
+        S3Source::with_client(Client::from_conf(config), "bucket", key_prefix)
+    }
+
+    #[test]
+    fn test_object_key_without_prefix() {
+        assert_eq!(source("").object_key("employment.json"), "employment.json");
+        assert_eq!(source("/").object_key("employment.json"), "employment.json");
+    }
+
+    #[test]
+    fn test_object_key_with_prefix() {
+        assert_eq!(
+            source("staging").object_key("employment.json"),
+            "staging/employment.json"
+        );
+        assert_eq!(
+            source("/prod/v2/").object_key("employment.json"),
+            "prod/v2/employment.json"
+        );
+    }
+
+    #[test]
+    fn test_cache_namespace_separates_endpoints() {
+        assert_eq!(
+            source("prod").cache_namespace().as_deref(),
+            Some("aws/eu-west-2/bucket/prod/")
+        );
+
+        let minio = S3Source {
+            endpoint_url: Some(String::from("http://localhost:9000")),
+            ..source("prod")
+        };
+        assert_eq!(
+            minio.cache_namespace().as_deref(),
+            Some("http___localhost_9000/eu-west-2/bucket/prod/")
+        );
+    }
+}
@@ -1,71 +1,148 @@
-use std::{
-    env,
-    time::{Duration, Instant},
-};
+use std::time::{Duration, Instant};
 
-use aws_config::{BehaviorVersion, Region};
-use aws_sdk_s3::Client;
+use crossterm::event::KeyEvent;
+use tui::layout::Rect;
 
 use crate::{
-    employment::{Education, Employment, EmploymentEducation},
-    settings::Settings,
-    ui::tabs::TabsHeadings,
+    employment::EmploymentEducation,
+    error::LoadError,
+    keymap::KeyBindings,
+    loader::LoadEvent,
+    resume::Resume,
+    source::Section,
+    ui::{tabs::TabsHeadings, theme::Theme},
 };
 
+/// Something a key press asks for that reaches outside the TUI, performed by
+/// the caller after the key has been handled.
+#[derive(Debug, PartialEq)]
+pub enum Effect {
+    CopyToClipboard(String),
+    OpenUrl(String),
+}
+
 pub struct State {
     pub selected_tab: TabsHeadings,
+    pub theme: Theme,
+    pub key_bindings: KeyBindings,
+    pub resume: Resume,
     pub selected_employment_entry: usize,
-    pub employment_history: Vec<Employment>,
     pub selected_education_entry: usize,
-    pub education_history: Vec<Education>,
+    pub selected_contact_field: usize,
+    /// A short note shown in the footer, e.g. after copying a contact field.
+    pub status_message: Option<String>,
+    pub should_quit: bool,
+    /// The start of a key sequence such as `g g`, waiting for the rest.
+    pub pending_keys: Vec<KeyEvent>,
     pub employment_or_education: EmploymentEducation,
     pub about_me_scroll_offset: u16,
+    pub details_scroll_offset: u16,
+    pub help_open: bool,
+    pub help_scroll_offset: u16,
+    /// The whole terminal as last drawn, for paging and mouse hits.
+    pub screen: Rect,
     last_tick: Instant,
     pub dot_count: u8,
     pub is_loading: bool,
+    pub pending_sections: Vec<Section>,
+    pub load_errors: Vec<(Section, LoadError)>,
+    load_requests: Vec<Section>,
+    effects: Vec<Effect>,
 }
 
 impl State {
     pub fn default() -> Self {
         Self {
             selected_tab: TabsHeadings::AboutMe,
+            theme: Theme::Green,
+            key_bindings: KeyBindings::default(),
+            resume: Resume::default(),
             selected_employment_entry: 0,
-            employment_history: Vec::new(),
             selected_education_entry: 0,
-            education_history: Vec::new(),
+            selected_contact_field: 0,
+            status_message: None,
+            should_quit: false,
+            pending_keys: Vec::new(),
             employment_or_education: EmploymentEducation::Employment,
             about_me_scroll_offset: 0,
+            details_scroll_offset: 0,
+            help_open: false,
+            help_scroll_offset: 0,
+            screen: Rect::default(),
             last_tick: Instant::now(),
             dot_count: 1,
             is_loading: false,
+            pending_sections: Vec::new(),
+            load_errors: Vec::new(),
+            load_requests: Vec::new(),
+            effects: Vec::new(),
         }
     }
 
+    pub fn quit(&mut self) {
+        self.should_quit = true;
+    }
+
     pub fn set_selected_tab(&mut self, tab: TabsHeadings) {
         self.selected_tab = tab;
     }
 
+    pub fn next_tab(&mut self) {
+        let next = (self.selected_tab.index() + 1) % TabsHeadings::ALL.len();
+        self.selected_tab = TabsHeadings::ALL[next];
+    }
+
+    pub fn previous_tab(&mut self) {
+        let count = TabsHeadings::ALL.len();
+        let previous = (self.selected_tab.index() + count - 1) % count;
+        self.selected_tab = TabsHeadings::ALL[previous];
+    }
+
     pub fn next_employer(&mut self) {
-        if self.selected_employment_entry != self.employment_history.len() - 1 {
-            self.selected_employment_entry = self.selected_employment_entry + 1
+        if self.selected_employment_entry + 1 < self.resume.employment.len() {
+            self.selected_employment_entry += 1;
+            self.details_scroll_offset = 0;
         }
     }
 
     pub fn previous_employer(&mut self) {
         if self.selected_employment_entry != 0 {
-            self.selected_employment_entry = self.selected_employment_entry - 1
+            self.selected_employment_entry -= 1;
+            self.details_scroll_offset = 0;
         }
     }
 
     pub fn next_educator(&mut self) {
-        if self.selected_education_entry != self.education_history.len() - 1 {
-            self.selected_education_entry = self.selected_education_entry + 1
+        if self.selected_education_entry + 1 < self.resume.education.len() {
+            self.selected_education_entry += 1;
+            self.details_scroll_offset = 0;
         }
     }
 
     pub fn previous_educator(&mut self) {
         if self.selected_education_entry != 0 {
-            self.selected_education_entry = self.selected_education_entry - 1
+            self.selected_education_entry -= 1;
+            self.details_scroll_offset = 0;
+        }
+    }
+
+    pub fn select_employment_entry(&mut self, index: usize) {
+        if self.employment_or_education != EmploymentEducation::Employment {
+            self.toggle_employment_or_education();
+        }
+        if index < self.resume.employment.len() && index != self.selected_employment_entry {
+            self.selected_employment_entry = index;
+            self.details_scroll_offset = 0;
+        }
+    }
+
+    pub fn select_education_entry(&mut self, index: usize) {
+        if self.employment_or_education != EmploymentEducation::Education {
+            self.toggle_employment_or_education();
+        }
+        if index < self.resume.education.len() && index != self.selected_education_entry {
+            self.selected_education_entry = index;
+            self.details_scroll_offset = 0;
         }
     }
 
@@ -73,94 +150,161 @@ impl State {
         self.employment_or_education = match self.employment_or_education {
             EmploymentEducation::Employment => EmploymentEducation::Education,
             EmploymentEducation::Education => EmploymentEducation::Employment,
-        }
+        };
+        self.details_scroll_offset = 0;
     }
 
-    pub fn _load_employment_from_file(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let cwd = env::current_dir()?;
-        let file_path = cwd.join("data/employment.json");
-        let json_data = std::fs::read_to_string(file_path)?;
-        let employment: Vec<Employment> = serde_json::from_str(&json_data)?;
-        self.employment_history = employment;
-        Ok(())
+    pub fn start_loading(&mut self, sections: &[Section]) {
+        self.pending_sections = sections.to_vec();
+        self.load_requests = sections.to_vec();
+        self.is_loading = !self.pending_sections.is_empty();
     }
 
-    pub fn _load_education_from_file(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let cwd = env::current_dir()?;
-        let file_path = cwd.join("data/education.json");
-        let json_data = std::fs::read_to_string(file_path)?;
-        let education: Vec<Education> = serde_json::from_str(&json_data)?;
-        self.education_history = education;
-        Ok(())
+    /// Sections that have been asked for since the last call, for the caller
+    /// to hand to a [`crate::loader::Loader`].
+    pub fn take_load_requests(&mut self) -> Vec<Section> {
+        std::mem::take(&mut self.load_requests)
     }
 
-    pub async fn load_employment_file_from_s3(
-        &mut self,
-        settings: &Settings,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let key = "employment.json";
+    pub fn is_pending(&self, section: Section) -> bool {
+        self.pending_sections.contains(&section)
+    }
 
-        let config_loader = aws_config::defaults(BehaviorVersion::latest());
-        let config = config_loader.region(Region::new("eu-west-2")).load().await;
+    pub fn load_error(&self, section: Section) -> Option<&LoadError> {
+        self.load_errors
+            .iter()
+            .find(|(s, _)| *s == section)
+            .map(|(_, err)| err)
+    }
 
-        let client = Client::new(&config);
+    pub fn retry_failed_loads(&mut self) {
+        for (section, _) in self.load_errors.drain(..) {
+            self.pending_sections.push(section);
+            self.load_requests.push(section);
+        }
+    }
 
-        // Fetch object from S3
-        let resp = client
-            .get_object()
-            .bucket(&settings.aws_bucket)
-            .key(key)
-            .send()
-            .await?;
+    pub fn apply_load_event(&mut self, event: LoadEvent) {
+        let section = event.section();
+        self.pending_sections.retain(|s| *s != section);
+        self.load_errors.retain(|(s, _)| *s != section);
+        self.is_loading = false;
+
+        let result = match event {
+            LoadEvent::Employment(result) => result.map(|e| self.resume.employment = e),
+            LoadEvent::Education(result) => result.map(|e| self.resume.education = e),
+            LoadEvent::Contact(result) => result.map(|c| self.resume.contact = c),
+            LoadEvent::AboutMe(result) => result.map(|a| self.resume.about = a),
+            LoadEvent::Profile(result) => result.map(|p| self.resume.profile = p),
+        };
+        if let Err(err) = result {
+            self.load_errors.push((section, err));
+        }
 
-        // Collect body bytes
-        let data = resp.body.collect().await?;
-        let bytes = data.into_bytes();
+        // Revalidation can replace a list with a shorter one.
+        self.selected_employment_entry = self
+            .selected_employment_entry
+            .min(self.resume.employment.len().saturating_sub(1));
+        self.selected_education_entry = self
+            .selected_education_entry
+            .min(self.resume.education.len().saturating_sub(1));
+        self.selected_contact_field = self
+            .selected_contact_field
+            .min(self.resume.contact.fields().len().saturating_sub(1));
+    }
 
-        // Deserialize JSON into struct
-        let employment: Vec<Employment> = serde_json::from_slice(&bytes)?;
-        self.employment_history = employment;
+    pub fn next_contact_field(&mut self) {
+        if self.selected_contact_field + 1 < self.resume.contact.fields().len() {
+            self.selected_contact_field += 1;
+        }
+    }
 
-        Ok(())
+    pub fn previous_contact_field(&mut self) {
+        if self.selected_contact_field != 0 {
+            self.selected_contact_field -= 1;
+        }
     }
 
-    pub async fn load_education_file_from_s3(
-        &mut self,
-        settings: &Settings,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let key = "education.json";
+    pub fn select_contact_field(&mut self, index: usize) {
+        if index < self.resume.contact.fields().len() {
+            self.selected_contact_field = index;
+        }
+    }
 
-        let config_loader = aws_config::defaults(BehaviorVersion::latest());
-        let config = config_loader.region(Region::new("eu-west-2")).load().await;
+    pub fn copy_selected_contact_field(&mut self) {
+        if let Some(field) = self
+            .resume
+            .contact
+            .fields()
+            .into_iter()
+            .nth(self.selected_contact_field)
+        {
+            self.effects.push(Effect::CopyToClipboard(field.value));
+        }
+    }
 
-        let client = Client::new(&config);
+    pub fn open_selected_contact_field(&mut self) {
+        let field = self
+            .resume
+            .contact
+            .fields()
+            .into_iter()
+            .nth(self.selected_contact_field);
+        match field.and_then(|field| field.url) {
+            Some(url) => self.effects.push(Effect::OpenUrl(url)),
+            None => self.status_message = Some(String::from("Nothing to open")),
+        }
+    }
 
-        // Fetch object from S3
-        let resp = client
-            .get_object()
-            .bucket(&settings.aws_bucket)
-            .key(key)
-            .send()
-            .await?;
+    pub fn take_effects(&mut self) -> Vec<Effect> {
+        std::mem::take(&mut self.effects)
+    }
 
-        // Collect body bytes
-        let data = resp.body.collect().await?;
-        let bytes = data.into_bytes();
+    /// Scrolls the About Me text down a line, no further than `max`.
+    pub fn scroll_about_me_down(&mut self, max: u16) {
+        if self.about_me_scroll_offset < max {
+            self.about_me_scroll_offset += 1;
+        }
+    }
 
-        // Deserialize JSON into struct
-        let education: Vec<Education> = serde_json::from_slice(&bytes)?;
-        self.education_history = education;
+    pub fn scroll_about_me_up(&mut self) {
+        if self.about_me_scroll_offset > 0 {
+            self.about_me_scroll_offset -= 1;
+        }
+    }
 
-        Ok(())
+    /// Scrolls the details down a line, no further than `max`.
+    pub fn scroll_details_down(&mut self, max: u16) {
+        if self.details_scroll_offset < max {
+            self.details_scroll_offset += 1;
+        }
     }
 
-    pub fn scroll_about_me_down(&mut self) {
-        self.about_me_scroll_offset = self.about_me_scroll_offset + 1;
+    pub fn scroll_details_up(&mut self) {
+        if self.details_scroll_offset > 0 {
+            self.details_scroll_offset -= 1;
+        }
     }
 
-    pub fn scroll_about_me_up(&mut self) {
-        if self.about_me_scroll_offset > 0 {
-            self.about_me_scroll_offset = self.about_me_scroll_offset - 1;
+    pub fn show_help(&mut self) {
+        self.help_open = true;
+        self.help_scroll_offset = 0;
+    }
+
+    pub fn close_help(&mut self) {
+        self.help_open = false;
+    }
+
+    /// Scrolls the help screen down a line, no further than `max`.
+    pub fn scroll_help_down(&mut self, max: u16) {
+        if self.help_scroll_offset < max {
+            self.help_scroll_offset += 1;
+        }
+    }
+
+    pub fn scroll_help_up(&mut self) {
+        if self.help_scroll_offset > 0 {
+            self.help_scroll_offset -= 1;
         }
     }
 
@@ -175,6 +319,18 @@ impl State {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        contact::ContactDetails,
+        employment::{Education, Employment},
+    };
+
+    fn sample_resume() -> Resume {
+        Resume {
+            employment: vec![Employment::_default(), Employment::_default()],
+            education: vec![Education::_default(), Education::_default()],
+            ..Resume::default()
+        }
+    }
 
     #[test]
     fn test_set_selected_tab() {
@@ -184,6 +340,18 @@ mod tests {
         assert_eq!(state.selected_tab, TabsHeadings::EmploymentAndEducation);
     }
 
+    #[test]
+    fn test_cycle_tabs() {
+        let mut state = State::default();
+        state.next_tab();
+        assert_eq!(state.selected_tab, TabsHeadings::ContactDetails);
+        state.next_tab();
+        state.next_tab();
+        assert_eq!(state.selected_tab, TabsHeadings::AboutMe);
+        state.previous_tab();
+        assert_eq!(state.selected_tab, TabsHeadings::EmploymentAndEducation);
+    }
+
     #[test]
     fn test_previous_employer_from_zero() {
         let mut state = State::default();
@@ -197,14 +365,11 @@ mod tests {
         let mut state = State {
             selected_tab: TabsHeadings::AboutMe,
             selected_employment_entry: 0,
-            employment_history: vec![Employment::_default(), Employment::_default()],
+            resume: sample_resume(),
             employment_or_education: EmploymentEducation::Employment,
             selected_education_entry: 1,
-            education_history: vec![Education::_default(), Education::_default()],
             about_me_scroll_offset: 0,
-            last_tick: Instant::now(),
-            dot_count: 1,
-            is_loading: false,
+            ..State::default()
         };
         assert_eq!(state.selected_employment_entry, 0);
         state.next_employer();
@@ -216,14 +381,11 @@ mod tests {
         let mut state = State {
             selected_tab: TabsHeadings::AboutMe,
             selected_employment_entry: 0,
-            employment_history: vec![Employment::_default(), Employment::_default()],
+            resume: sample_resume(),
             employment_or_education: EmploymentEducation::Employment,
             selected_education_entry: 1,
-            education_history: vec![Education::_default(), Education::_default()],
             about_me_scroll_offset: 0,
-            last_tick: Instant::now(),
-            dot_count: 1,
-            is_loading: false,
+            ..State::default()
         };
         assert_eq!(state.selected_employment_entry, 0);
         state.next_employer();
@@ -237,14 +399,11 @@ mod tests {
         let mut state = State {
             selected_tab: TabsHeadings::AboutMe,
             selected_employment_entry: 1,
-            employment_history: vec![Employment::_default(), Employment::_default()],
+            resume: sample_resume(),
             employment_or_education: EmploymentEducation::Employment,
             selected_education_entry: 1,
-            education_history: vec![Education::_default(), Education::_default()],
             about_me_scroll_offset: 0,
-            last_tick: Instant::now(),
-            dot_count: 1,
-            is_loading: false,
+            ..State::default()
         };
         assert_eq!(state.selected_employment_entry, 1);
         state.next_employer();
@@ -256,14 +415,11 @@ mod tests {
         let mut state = State {
             selected_tab: TabsHeadings::AboutMe,
             selected_employment_entry: 1,
-            employment_history: vec![Employment::_default(), Employment::_default()],
+            resume: sample_resume(),
             employment_or_education: EmploymentEducation::Employment,
             selected_education_entry: 1,
-            education_history: vec![Education::_default(), Education::_default()],
             about_me_scroll_offset: 0,
-            last_tick: Instant::now(),
-            dot_count: 1,
-            is_loading: false,
+            ..State::default()
         };
         assert_eq!(state.selected_employment_entry, 1);
         state.next_employer();
@@ -285,14 +441,11 @@ mod tests {
         let mut state = State {
             selected_tab: TabsHeadings::AboutMe,
             selected_employment_entry: 1,
-            employment_history: vec![Employment::_default(), Employment::_default()],
+            resume: sample_resume(),
             employment_or_education: EmploymentEducation::Employment,
             selected_education_entry: 0,
-            education_history: vec![Education::_default(), Education::_default()],
             about_me_scroll_offset: 0,
-            last_tick: Instant::now(),
-            dot_count: 1,
-            is_loading: false,
+            ..State::default()
         };
         assert_eq!(state.selected_education_entry, 0);
         state.next_educator();
@@ -304,14 +457,11 @@ mod tests {
         let mut state = State {
             selected_tab: TabsHeadings::AboutMe,
             selected_employment_entry: 1,
-            employment_history: vec![Employment::_default(), Employment::_default()],
+            resume: sample_resume(),
             employment_or_education: EmploymentEducation::Employment,
             selected_education_entry: 0,
-            education_history: vec![Education::_default(), Education::_default()],
             about_me_scroll_offset: 0,
-            last_tick: Instant::now(),
-            dot_count: 1,
-            is_loading: false,
+            ..State::default()
         };
         assert_eq!(state.selected_education_entry, 0);
         state.next_educator();
@@ -325,14 +475,11 @@ mod tests {
         let mut state = State {
             selected_tab: TabsHeadings::AboutMe,
             selected_employment_entry: 1,
-            employment_history: vec![Employment::_default(), Employment::_default()],
+            resume: sample_resume(),
             employment_or_education: EmploymentEducation::Employment,
             selected_education_entry: 1,
-            education_history: vec![Education::_default(), Education::_default()],
             about_me_scroll_offset: 0,
-            last_tick: Instant::now(),
-            dot_count: 1,
-            is_loading: false,
+            ..State::default()
         };
         assert_eq!(state.selected_education_entry, 1);
         state.next_educator();
@@ -344,14 +491,11 @@ mod tests {
         let mut state = State {
             selected_tab: TabsHeadings::AboutMe,
             selected_employment_entry: 1,
-            employment_history: vec![Employment::_default(), Employment::_default()],
+            resume: sample_resume(),
             employment_or_education: EmploymentEducation::Employment,
             selected_education_entry: 1,
-            education_history: vec![Education::_default(), Education::_default()],
             about_me_scroll_offset: 0,
-            last_tick: Instant::now(),
-            dot_count: 1,
-            is_loading: false,
+            ..State::default()
         };
         assert_eq!(state.selected_education_entry, 1);
         state.next_educator();
@@ -360,6 +504,50 @@ mod tests {
         assert_eq!(state.selected_education_entry, 0);
     }
 
+    #[test]
+    fn test_next_employer_with_no_history() {
+        let mut state = State::default();
+        state.next_employer();
+        assert_eq!(state.selected_employment_entry, 0);
+    }
+
+    #[test]
+    fn test_apply_load_event_with_partial_arrival() {
+        let mut state = State::default();
+        state.start_loading(&[Section::Employment, Section::Education]);
+        assert!(state.is_loading);
+
+        state.apply_load_event(LoadEvent::Employment(Ok(vec![Employment::_default()])));
+        assert!(!state.is_loading);
+        assert_eq!(state.resume.employment.len(), 1);
+        assert!(!state.is_pending(Section::Employment));
+        assert!(state.is_pending(Section::Education));
+
+        state.apply_load_event(LoadEvent::Education(Err(LoadError::MissingCredentials)));
+        assert!(!state.is_pending(Section::Education));
+        assert!(state.resume.education.is_empty());
+    }
+
+    #[test]
+    fn test_retry_failed_loads() {
+        let mut state = State::default();
+        state.start_loading(&[Section::Employment, Section::Education]);
+        assert_eq!(state.take_load_requests().len(), 2);
+        assert!(state.take_load_requests().is_empty());
+
+        state.apply_load_event(LoadEvent::Employment(Ok(Vec::new())));
+        state.apply_load_event(LoadEvent::Education(Err(LoadError::BucketNotFound(
+            String::from("bucket"),
+        ))));
+        assert!(state.load_error(Section::Education).is_some());
+        assert!(state.load_error(Section::Employment).is_none());
+
+        state.retry_failed_loads();
+        assert!(state.load_errors.is_empty());
+        assert!(state.is_pending(Section::Education));
+        assert_eq!(state.take_load_requests(), vec![Section::Education]);
+    }
+
     #[test]
     fn test_toggle_employment_or_education() {
         let mut state = State::default();
@@ -383,7 +571,9 @@ mod tests {
     fn test_scroll_about_me_down() {
         let mut state = State::default();
         assert_eq!(state.about_me_scroll_offset, 0);
-        state.scroll_about_me_down();
+        state.scroll_about_me_down(1);
+        assert_eq!(state.about_me_scroll_offset, 1);
+        state.scroll_about_me_down(1);
         assert_eq!(state.about_me_scroll_offset, 1);
     }
 
@@ -392,20 +582,76 @@ mod tests {
         let mut state = State {
             selected_tab: TabsHeadings::AboutMe,
             selected_employment_entry: 1,
-            employment_history: vec![Employment::_default(), Employment::_default()],
+            resume: sample_resume(),
             employment_or_education: EmploymentEducation::Employment,
             selected_education_entry: 1,
-            education_history: vec![Education::_default(), Education::_default()],
             about_me_scroll_offset: 1,
-            last_tick: Instant::now(),
-            dot_count: 1,
-            is_loading: false,
+            ..State::default()
         };
         assert_eq!(state.about_me_scroll_offset, 1);
         state.scroll_about_me_up();
         assert_eq!(state.about_me_scroll_offset, 0);
     }
 
+    #[test]
+    fn test_changing_selection_resets_details_scroll() {
+        let mut state = State {
+            resume: sample_resume(),
+            ..State::default()
+        };
+        state.scroll_details_down(2);
+        state.scroll_details_down(2);
+        state.scroll_details_down(2);
+        assert_eq!(state.details_scroll_offset, 2);
+        state.scroll_details_up();
+        assert_eq!(state.details_scroll_offset, 1);
+
+        state.next_employer();
+        assert_eq!(state.details_scroll_offset, 0);
+
+        state.scroll_details_down(2);
+        state.toggle_employment_or_education();
+        assert_eq!(state.details_scroll_offset, 0);
+        state.scroll_details_up();
+        assert_eq!(state.details_scroll_offset, 0);
+    }
+
+    #[test]
+    fn test_contact_field_actions() {
+        let mut state = State {
+            resume: Resume {
+                contact: ContactDetails {
+                    email: Some(String::from("me@example.com")),
+                    location: Some(String::from("London")),
+                    ..ContactDetails::default()
+                },
+                ..Resume::default()
+            },
+            ..State::default()
+        };
+
+        state.copy_selected_contact_field();
+        state.open_selected_contact_field();
+        assert_eq!(
+            state.take_effects(),
+            vec![
+                Effect::CopyToClipboard(String::from("me@example.com")),
+                Effect::OpenUrl(String::from("mailto:me@example.com")),
+            ]
+        );
+
+        state.next_contact_field();
+        state.next_contact_field();
+        assert_eq!(state.selected_contact_field, 1);
+        state.open_selected_contact_field();
+        assert!(state.take_effects().is_empty());
+        assert!(state.status_message.is_some());
+
+        state.previous_contact_field();
+        state.previous_contact_field();
+        assert_eq!(state.selected_contact_field, 0);
+    }
+
     #[test]
     fn test_scroll_about_me_up_does_not_underflow() {
         let mut state = State::default();
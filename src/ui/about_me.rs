@@ -1,61 +1,49 @@
-use std::env;
-
-use serde::Deserialize;
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
+    text::Text,
     widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
 };
 
-use crate::state::State;
+use crate::{source::Section, state::State, ui};
 
-#[derive(Debug, Deserialize)]
-struct AboutMe {
-    pub about_me: String,
+/// The title above the text, then the text itself.
+pub fn layout(chunk: Rect) -> Vec<Rect> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(chunk)
 }
 
-impl AboutMe {
-    pub fn default() -> Self {
-        Self {
-            about_me: String::from(""),
-        }
-    }
-
-    pub fn from_file() -> Result<Self, Box<dyn std::error::Error>> {
-        let cwd = env::current_dir()?;
-        let file_path = cwd.join("data/about_me.json");
-        let json_data = std::fs::read_to_string(file_path)?;
-        let about_me: Self = serde_json::from_str(&json_data)?;
-        Ok(about_me)
-    }
+/// How far the text scrolls before its last line reaches the bottom.
+pub fn max_scroll(state: &State) -> u16 {
+    let text = layout(ui::app_layout(state.screen)[2])[1];
+    let lines = Text::from(state.resume.about.about_me.as_str()).lines;
+    ui::wrapped_height(&lines, text.width, true).saturating_sub(text.height)
 }
 
 pub fn render_about_me<B: Backend>(f: &mut Frame<B>, chunk: Rect, state: &State) {
-    let top_bottom = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Min(0)])
-        .split(chunk);
+    let top_bottom = layout(chunk);
 
-    let me = match AboutMe::from_file() {
-        Ok(me) => me,
-        Err(_) => AboutMe::default(),
+    let title = match state.is_pending(Section::AboutMe) {
+        true => "About me (loading...)",
+        false => "About me",
     };
-
-    let title = Paragraph::new("About me")
+    let title = Paragraph::new(title)
         .style(
             Style::default()
-                .fg(Color::Green)
+                .fg(state.theme.accent())
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::NONE));
 
-    let about_me = Paragraph::new(me.about_me)
+    let about_me = Paragraph::new(state.resume.about.about_me.as_str())
         .style(
             Style::default()
-                .fg(Color::Green)
+                .fg(state.theme.accent())
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Left)
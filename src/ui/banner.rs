@@ -1,18 +1,18 @@
 use tui::{
     backend::Backend,
     layout::{Alignment, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
-use crate::assets::banner;
+use crate::{assets::banner, ui::theme::Theme};
 
-pub fn render_banner<B: Backend>(f: &mut Frame<B>, chunk: Rect) {
+pub fn render_banner<B: Backend>(f: &mut Frame<B>, chunk: Rect, theme: Theme) {
     let block = Paragraph::new(banner::ASCII_ART)
         .style(
             Style::default()
-                .fg(Color::Green)
+                .fg(theme.accent())
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center)
@@ -1,19 +1,69 @@
 use tui::{
     backend::Backend,
-    layout::{Alignment, Rect},
-    style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Paragraph},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
     Frame,
 };
 
-pub fn render_contact_details<B: Backend>(f: &mut Frame<B>, chunk: Rect) {
-    let about_me = Paragraph::new("Contact information")
+use crate::{source::Section, state::State};
+
+pub fn render_contact_details<B: Backend>(f: &mut Frame<B>, chunk: Rect, state: &State) {
+    let top_bottom = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(chunk);
+
+    let title = Paragraph::new("Contact information")
         .style(
             Style::default()
-                .fg(Color::Green)
+                .fg(state.theme.accent())
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::NONE));
-    f.render_widget(about_me, chunk);
+
+    let fields = state.resume.contact.fields();
+    let label_width = fields
+        .iter()
+        .map(|field| field.label.len() as u16)
+        .max()
+        .unwrap_or(0)
+        + 2;
+
+    let rows: Vec<Row> = fields
+        .into_iter()
+        .map(|field| {
+            Row::new(vec![
+                Cell::from(field.label).style(
+                    Style::default()
+                        .fg(state.theme.accent())
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Cell::from(field.value),
+            ])
+        })
+        .collect();
+
+    let mut block = Block::default().borders(Borders::ALL);
+    if state.is_pending(Section::Contact) {
+        block = block.title("Loading...");
+    }
+
+    let widths = [Constraint::Length(label_width), Constraint::Min(0)];
+    let table = Table::new(rows)
+        .widths(&widths)
+        .block(block)
+        .highlight_symbol(" * ")
+        .highlight_style(
+            Style::default()
+                .fg(state.theme.focus())
+                .add_modifier(Modifier::BOLD),
+        );
+
+    let mut table_state = TableState::default();
+    table_state.select(Some(state.selected_contact_field));
+
+    f.render_widget(title, top_bottom[0]);
+    f.render_stateful_widget(table, top_bottom[1], &mut table_state);
 }
@@ -1,17 +1,31 @@
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 
-use crate::{employment::EmploymentEducation, state::State};
+use crate::{
+    employment::{Details, EmploymentEducation},
+    source::Section,
+    state::State,
+    ui,
+};
+
+fn list_title(title: &str, section: Section, state: &State) -> String {
+    match state.is_pending(section) {
+        true => format!("{} (loading...)", title),
+        false => title.to_string(),
+    }
+}
 
 fn employment_block<'a>(list_state: &mut ListState, state: &State) -> List<'a> {
     list_state.select(Some(state.selected_employment_entry));
     let employment_list_items: Vec<ListItem> = state
-        .employment_history
+        .resume
+        .employment
         .iter()
         .enumerate()
         .map(|(i, s)| {
@@ -25,11 +39,13 @@ fn employment_block<'a>(list_state: &mut ListState, state: &State) -> List<'a> {
         })
         .collect();
 
-    let mut block = Block::default().title("Employment").borders(Borders::ALL);
+    let mut block = Block::default()
+        .title(list_title("Employment", Section::Employment, state))
+        .borders(Borders::ALL);
     if state.employment_or_education == EmploymentEducation::Employment {
         block = block.border_style(
             Style::default()
-                .fg(Color::White)
+                .fg(state.theme.focus())
                 .add_modifier(Modifier::BOLD),
         );
     }
@@ -46,7 +62,8 @@ fn employment_block<'a>(list_state: &mut ListState, state: &State) -> List<'a> {
 fn education_block<'a>(list_state: &mut ListState, state: &State) -> List<'a> {
     list_state.select(Some(state.selected_education_entry));
     let education_list_items: Vec<ListItem> = state
-        .education_history
+        .resume
+        .education
         .iter()
         .enumerate()
         .map(|(i, s)| {
@@ -60,11 +77,13 @@ fn education_block<'a>(list_state: &mut ListState, state: &State) -> List<'a> {
         })
         .collect();
 
-    let mut block = Block::default().title("Education").borders(Borders::ALL);
+    let mut block = Block::default()
+        .title(list_title("Education", Section::Education, state))
+        .borders(Borders::ALL);
     if state.employment_or_education == EmploymentEducation::Education {
         block = block.border_style(
             Style::default()
-                .fg(Color::White)
+                .fg(state.theme.focus())
                 .add_modifier(Modifier::BOLD),
         );
     }
@@ -78,7 +97,104 @@ fn education_block<'a>(list_state: &mut ListState, state: &State) -> List<'a> {
     list
 }
 
-pub fn render_employment<B: Backend>(f: &mut Frame<B>, chunk: Rect, state: &State) {
+fn detail_lines<'a>(
+    heading: &'a str,
+    subheading: &'a str,
+    dates: String,
+    details: &'a Details,
+    state: &State,
+) -> Vec<Spans<'a>> {
+    let accent = Style::default().fg(state.theme.accent());
+    let bold = accent.add_modifier(Modifier::BOLD);
+
+    let mut lines = vec![
+        Spans::from(Span::styled(heading, bold)),
+        Spans::from(Span::styled(subheading, accent)),
+        Spans::from(dates),
+    ];
+    if let Some(location) = &details.location {
+        lines.push(Spans::from(location.as_str()));
+    }
+    if let Some(link) = &details.link {
+        lines.push(Spans::from(Span::styled(
+            link.as_str(),
+            Style::default().add_modifier(Modifier::UNDERLINED),
+        )));
+    }
+
+    if let Some(summary) = &details.summary {
+        lines.push(Spans::default());
+        lines.push(Spans::from(summary.as_str()));
+    }
+
+    if !details.achievements.is_empty() {
+        lines.push(Spans::default());
+        lines.push(Spans::from(Span::styled("Achievements", bold)));
+        for achievement in &details.achievements {
+            lines.push(Spans::from(format!(" • {}", achievement)));
+        }
+    }
+
+    if !details.technologies.is_empty() {
+        lines.push(Spans::default());
+        lines.push(Spans::from(vec![
+            Span::styled("Technologies: ", bold),
+            Span::raw(details.technologies.join(", ")),
+        ]));
+    }
+
+    lines
+}
+
+/// The details of the selected entry, of whichever list has focus.
+fn selected_details(state: &State) -> Vec<Spans<'_>> {
+    let lines = match state.employment_or_education {
+        EmploymentEducation::Employment => state
+            .resume
+            .employment
+            .get(state.selected_employment_entry)
+            .map(|e| {
+                detail_lines(
+                    &e.role,
+                    &e.employer,
+                    format!("{} - {}", e.start_date, e.end_date),
+                    &e.details,
+                    state,
+                )
+            }),
+        EmploymentEducation::Education => state
+            .resume
+            .education
+            .get(state.selected_education_entry)
+            .map(|e| {
+                detail_lines(
+                    &e.qualification,
+                    &e.educator,
+                    format!("{} - {}", e.start_date, e.end_date),
+                    &e.details,
+                    state,
+                )
+            }),
+    };
+    lines.unwrap_or_default()
+}
+
+fn details_block<'a>(state: &'a State) -> Paragraph<'a> {
+    Paragraph::new(selected_details(state))
+        .wrap(Wrap { trim: false })
+        .scroll((state.details_scroll_offset, 0))
+        .block(Block::default().title("Details").borders(Borders::ALL))
+}
+
+/// Where the parts of the employment tab are drawn.
+pub struct EmploymentLayout {
+    pub title: Rect,
+    pub employment: Rect,
+    pub education: Rect,
+    pub details: Rect,
+}
+
+pub fn layout(chunk: Rect) -> EmploymentLayout {
     let top_bottom = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Percentage(5), Constraint::Percentage(95)])
@@ -94,25 +210,111 @@ pub fn render_employment<B: Backend>(f: &mut Frame<B>, chunk: Rect, state: &Stat
         .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
         .split(bottom_chunks[0]);
 
+    EmploymentLayout {
+        title: top_bottom[0],
+        employment: list_chuncks[0],
+        education: list_chuncks[1],
+        details: bottom_chunks[1],
+    }
+}
+
+/// How far the details scroll before their last line reaches the bottom.
+pub fn max_details_scroll(state: &State) -> u16 {
+    let details = layout(ui::app_layout(state.screen)[2]).details;
+    let inner = Block::default().borders(Borders::ALL).inner(details);
+    ui::wrapped_height(&selected_details(state), inner.width, false).saturating_sub(inner.height)
+}
+
+/// Which entry of a list drawn in `area` is at `row`, given the height of
+/// each entry and the one selected. `List` scrolls just far enough to show
+/// the selected entry, so this does the same.
+pub fn entry_at(area: Rect, heights: &[usize], selected: usize, row: u16) -> Option<usize> {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    if heights.is_empty() || row < inner.top() || row >= inner.bottom() {
+        return None;
+    }
+
+    let max_height = inner.height as usize;
+    let selected = selected.min(heights.len() - 1);
+    let mut start = 0;
+    let mut height: usize = heights.iter().take(selected + 1).sum();
+    while height > max_height && start < selected {
+        height -= heights[start];
+        start += 1;
+    }
+
+    let mut top = inner.top() as usize;
+    for (i, item_height) in heights.iter().enumerate().skip(start) {
+        if (row as usize) < top + item_height {
+            return Some(i);
+        }
+        top += item_height;
+    }
+    None
+}
+
+pub fn employment_heights(state: &State) -> Vec<usize> {
+    state
+        .resume
+        .employment
+        .iter()
+        .map(|e| ListItem::new(e.representation(false)).height())
+        .collect()
+}
+
+pub fn education_heights(state: &State) -> Vec<usize> {
+    state
+        .resume
+        .education
+        .iter()
+        .map(|e| ListItem::new(e.representation(false)).height())
+        .collect()
+}
+
+pub fn render_employment<B: Backend>(f: &mut Frame<B>, chunk: Rect, state: &State) {
+    let layout = layout(chunk);
+
     let title = Paragraph::new("Employment and Education History")
         .style(
             Style::default()
-                .fg(Color::Green)
+                .fg(state.theme.accent())
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::NONE));
 
     let mut employment_list_state = ListState::default();
-    let employment_list = employment_block(&mut employment_list_state, &state);
+    let employment_list = employment_block(&mut employment_list_state, state);
 
     let mut education_list_state = ListState::default();
-    let education_list = education_block(&mut education_list_state, &state);
+    let education_list = education_block(&mut education_list_state, state);
+
+    let description = details_block(state);
 
-    let description = Block::default();
+    f.render_widget(title, layout.title);
+    f.render_stateful_widget(
+        employment_list,
+        layout.employment,
+        &mut employment_list_state,
+    );
+    f.render_stateful_widget(education_list, layout.education, &mut education_list_state);
+    f.render_widget(description, layout.details);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    f.render_widget(title, top_bottom[0]);
-    f.render_stateful_widget(employment_list, list_chuncks[0], &mut employment_list_state);
-    f.render_stateful_widget(education_list, list_chuncks[1], &mut education_list_state);
-    f.render_widget(description, bottom_chunks[1]);
+    #[test]
+    fn test_entry_at() {
+        let area = Rect::new(0, 10, 20, 10);
+        let heights = [4, 4, 4];
+        assert_eq!(entry_at(area, &heights, 0, 10), None);
+        assert_eq!(entry_at(area, &heights, 0, 11), Some(0));
+        assert_eq!(entry_at(area, &heights, 0, 15), Some(1));
+        assert_eq!(entry_at(area, &heights, 0, 19), None);
+        // Selecting the last entry scrolls the first out of view.
+        assert_eq!(entry_at(area, &heights, 2, 11), Some(1));
+        assert_eq!(entry_at(area, &heights, 2, 15), Some(2));
+    }
 }
@@ -0,0 +1,112 @@
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    events::{Context, Event},
+    source::Section,
+    state::State,
+    ui::tabs::TabsHeadings,
+};
+
+fn tab_sections(tab: &TabsHeadings) -> &'static [Section] {
+    match tab {
+        TabsHeadings::EmploymentAndEducation => &[Section::Employment, Section::Education],
+        TabsHeadings::ContactDetails => &[Section::Contact],
+        TabsHeadings::AboutMe => &[Section::AboutMe, Section::Profile],
+    }
+}
+
+/// Draws `lines` in a panel of the theme's error colour pinned to the
+/// bottom of `chunk`, on top of whatever has already been rendered there.
+pub fn render_error_panel<B: Backend>(
+    f: &mut Frame<B>,
+    chunk: Rect,
+    lines: Vec<String>,
+    state: &State,
+) {
+    let error = state.theme.error();
+    let height = (lines.len() as u16 + 2).min(chunk.height);
+    let area = Rect {
+        x: chunk.x,
+        y: chunk.y + chunk.height - height,
+        width: chunk.width,
+        height,
+    };
+
+    let panel = Paragraph::new(lines.join("\n"))
+        .style(Style::default().fg(error))
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .title("Error")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(error).add_modifier(Modifier::BOLD)),
+        );
+
+    f.render_widget(Clear, area);
+    f.render_widget(panel, area);
+}
+
+/// The load errors of the sections on the current tab, and how to retry
+/// them if retrying has a key.
+fn load_error_lines(state: &State) -> Vec<String> {
+    let mut lines: Vec<String> = tab_sections(&state.selected_tab)
+        .iter()
+        .filter_map(|section| state.load_error(*section))
+        .map(|err| format!("Could not load resume data: {}", err))
+        .collect();
+
+    if lines.is_empty() {
+        return lines;
+    }
+
+    let bindings = state.key_bindings.bindings_for(Context::Global);
+    if let Some((_, keys)) = bindings.iter().find(|(event, _)| *event == Event::Retry) {
+        lines.push(format!("Press {} to retry.", keys[0]));
+    }
+    lines
+}
+
+pub fn render_load_errors<B: Backend>(f: &mut Frame<B>, chunk: Rect, state: &State) {
+    let lines = load_error_lines(state);
+    if !lines.is_empty() {
+        render_error_panel(f, chunk, lines, state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{error::LoadError, keymap::KeyBindings};
+
+    #[test]
+    fn test_retry_hint_follows_bindings() {
+        let mut state = State::default();
+        assert!(load_error_lines(&state).is_empty());
+
+        state.load_errors.push((
+            Section::AboutMe,
+            LoadError::NotFound(String::from("about_me.json")),
+        ));
+        assert_eq!(load_error_lines(&state)[1], "Press r to retry.");
+
+        let preset = |name: &str| (String::from("preset"), vec![name.to_string()]);
+        state.key_bindings = KeyBindings::new(&BTreeMap::from([preset("emacs")])).unwrap();
+        assert_eq!(load_error_lines(&state)[1], "Press g to retry.");
+
+        state.key_bindings = KeyBindings::new(&BTreeMap::from([
+            preset("default"),
+            (String::from("retry"), vec![]),
+        ]))
+        .unwrap();
+        assert_eq!(load_error_lines(&state).len(), 1);
+    }
+}
@@ -0,0 +1,131 @@
+use tui::{
+    backend::Backend,
+    layout::{Margin, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    events::{Context, Event},
+    state::State,
+};
+
+/// Where the help screen is drawn: over everything, inside the app's margin.
+pub fn layout(area: Rect) -> Rect {
+    area.inner(&Margin {
+        vertical: 2,
+        horizontal: 2,
+    })
+}
+
+/// The lines of the help screen that fit at once.
+pub fn page_height(state: &State) -> u16 {
+    let inner = Block::default()
+        .borders(Borders::ALL)
+        .inner(layout(state.screen));
+    inner.height.max(1)
+}
+
+/// How far the help screen scrolls before its last line reaches the bottom.
+pub fn max_scroll(state: &State) -> u16 {
+    let lines = help_lines(state).len() as u16;
+    lines.saturating_sub(page_height(state))
+}
+
+/// Every binding, grouped under the context it applies in.
+fn help_lines(state: &State) -> Vec<Spans<'_>> {
+    let heading = Style::default()
+        .fg(state.theme.accent())
+        .add_modifier(Modifier::BOLD);
+
+    let groups: Vec<_> = Context::ALL
+        .into_iter()
+        .map(|context| {
+            let bindings: Vec<(Event, String)> = state
+                .key_bindings
+                .bindings_in(context)
+                .into_iter()
+                .map(|(event, keys)| (event, keys.join(", ")))
+                .collect();
+            (context, bindings)
+        })
+        .filter(|(_, bindings)| !bindings.is_empty())
+        .collect();
+    let width = groups
+        .iter()
+        .flat_map(|(_, bindings)| bindings.iter().map(|(_, keys)| keys.len()))
+        .max()
+        .unwrap_or(0);
+
+    let mut lines = Vec::new();
+    for (context, bindings) in groups {
+        if !lines.is_empty() {
+            lines.push(Spans::default());
+        }
+        lines.push(Spans::from(Span::styled(context.title(), heading)));
+        for (event, keys) in bindings {
+            lines.push(Spans::from(format!(
+                "  {:<width$}  {}",
+                keys,
+                event.description()
+            )));
+        }
+    }
+    lines
+}
+
+pub fn render_help<B: Backend>(f: &mut Frame<B>, area: Rect, state: &State) {
+    let area = layout(area);
+    let close = state
+        .key_bindings
+        .bindings_in(Context::Help)
+        .into_iter()
+        .find(|(event, _)| *event == Event::CloseHelp)
+        .map(|(_, keys)| keys[0].to_string());
+    let title = match close {
+        Some(key) => format!("Keys ({} to close)", key),
+        None => String::from("Keys"),
+    };
+
+    let help = Paragraph::new(help_lines(state))
+        .scroll((state.help_scroll_offset, 0))
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(
+                    Style::default()
+                        .fg(state.theme.focus())
+                        .add_modifier(Modifier::BOLD),
+                ),
+        );
+
+    f.render_widget(Clear, area);
+    f.render_widget(help, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_lines() {
+        let state = State::default();
+        let text: Vec<String> = help_lines(&state)
+            .iter()
+            .map(|line| line.0.iter().map(|span| span.content.as_ref()).collect())
+            .collect();
+
+        for context in Context::ALL {
+            assert!(text.contains(&context.title().to_string()));
+        }
+        assert!(text
+            .iter()
+            .any(|line| line.starts_with("  q ") && line.ends_with("quit")));
+        assert!(text
+            .iter()
+            .any(|line| line.starts_with("  j, Down ") && line.ends_with("next employer")));
+    }
+}
@@ -6,12 +6,12 @@ use tui::{
     Frame,
 };
 
-use crate::{employment::EmploymentEducation, events::Event, state::State, ui::tabs::TabsHeadings};
+use crate::{events::Event, keymap, state::State};
 
-fn style_keybind(event: &Event) -> String {
+fn style_keybind(event: &Event, key: &str) -> String {
     let styled_vec = vec![
         Span::raw(" <"),
-        Span::raw(event.key_binding()),
+        Span::raw(key),
         Span::raw(": "),
         Span::raw(event.description()),
         Span::raw("> "),
@@ -24,39 +24,22 @@ fn style_keybind(event: &Event) -> String {
 }
 
 pub fn render_keymap<B: Backend>(f: &mut Frame<B>, chunk: Rect, state: &State) {
-    let mut base_events = vec![
-        Event::Quit,
-        Event::SwitchToAboutMe,
-        Event::SwitchToContactInformation,
-        Event::SwitchToEmployment,
-    ];
-
-    match state.selected_tab {
-        TabsHeadings::EmploymentAndEducation => match state.employment_or_education {
-            EmploymentEducation::Employment => {
-                base_events.push(Event::SelectEducation);
-                base_events.push(Event::NextEmployer);
-                base_events.push(Event::PreviousEmployer);
-            }
-            EmploymentEducation::Education => {
-                base_events.push(Event::SelectEmployment);
-                base_events.push(Event::NextEducation);
-                base_events.push(Event::PreviousEducation);
-            }
-        },
-        TabsHeadings::AboutMe => {
-            base_events.push(Event::ScrollAboutMeTextDown);
-            base_events.push(Event::ScrollAboutMeTextUp);
-        }
-        _ => {}
-    }
-
-    let line = base_events
+    let line = state
+        .key_bindings
+        .bindings_for(keymap::context(state))
         .into_iter()
-        .map(|e| style_keybind(&e))
+        .filter(|(event, _)| event.in_footer())
+        // Retrying only means something once a section has failed to load.
+        .filter(|(event, _)| *event != Event::Retry || !state.load_errors.is_empty())
+        // Only the first key fits; the help screen lists the rest.
+        .map(|(event, keys)| style_keybind(&event, keys[0]))
         .collect::<String>();
 
-    let block = Paragraph::new(line).block(Block::default().borders(Borders::ALL));
+    let mut block = Block::default().borders(Borders::ALL);
+    if let Some(message) = &state.status_message {
+        block = block.title(message.as_str());
+    }
+    let block = Paragraph::new(line).block(block);
 
     f.render_widget(block, chunk);
 }
@@ -2,6 +2,110 @@ pub mod about_me;
 pub mod banner;
 pub mod contact_details;
 pub mod employment;
+pub mod error;
+pub mod help;
 pub mod keymap;
 pub mod loading;
 pub mod tabs;
+pub mod theme;
+
+use tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    text::Spans,
+};
+
+/// The banner, tab bar, current tab and footer, top to bottom.
+pub fn app_layout(area: Rect) -> Vec<Rect> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(9),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(area)
+}
+
+/// How many rows `lines` take up once wrapped to `width` columns by a
+/// `Paragraph`: greedily by word, splitting words wider than a row.
+/// `trim` drops each line's indentation, as `Wrap { trim: true }` does.
+pub fn wrapped_height(lines: &[Spans], width: u16, trim: bool) -> u16 {
+    let width = width.max(1) as usize;
+    let mut rows = 0;
+    for spans in lines {
+        let line: String = spans.0.iter().map(|span| span.content.as_ref()).collect();
+        let indent = match trim {
+            true => 0,
+            false => line.chars().take_while(|c| c.is_whitespace()).count(),
+        };
+        let mut used = indent.min(width);
+        rows += 1;
+        for (i, word) in line.split_whitespace().enumerate() {
+            let mut len = word.chars().count();
+            if i > 0 && used + 1 + len > width {
+                rows += 1;
+                used = 0;
+            } else if i > 0 {
+                used += 1;
+            }
+            while used + len > width {
+                len -= width - used;
+                rows += 1;
+                used = 0;
+            }
+            used += len;
+        }
+    }
+    rows.min(u16::MAX as usize) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tui::{
+        buffer::Buffer,
+        text::Text,
+        widgets::{Paragraph, Widget, Wrap},
+    };
+
+    /// The rows a `Paragraph` actually draws on, down to the last one used.
+    fn rendered_height(text: &str, width: u16, trim: bool) -> u16 {
+        let area = Rect::new(0, 0, width, 100);
+        let mut buffer = Buffer::empty(area);
+        Paragraph::new(text)
+            .wrap(Wrap { trim })
+            .render(area, &mut buffer);
+        (0..area.height)
+            .rev()
+            .find(|&y| (0..width).any(|x| buffer.get(x, y).symbol != " "))
+            .map_or(0, |y| y + 1)
+    }
+
+    #[test]
+    fn test_wrapped_height_matches_paragraph() {
+        let texts = [
+            "short",
+            "a few words that wrap around the edge of a narrow pane",
+            " • an indented bullet point long enough to wrap twice over",
+            "averyveryverylongwordthatcannotfitonanyline and then some",
+            "first paragraph\n\nsecond paragraph, after a blank line, ends here",
+        ];
+        for text in texts {
+            for width in [10, 17, 24] {
+                for trim in [true, false] {
+                    let lines = Text::from(text).lines;
+                    assert_eq!(
+                        wrapped_height(&lines, width, trim),
+                        rendered_height(text, width, trim),
+                        "{:?} at {} columns, trim {}",
+                        text,
+                        width,
+                        trim
+                    );
+                }
+            }
+        }
+    }
+}
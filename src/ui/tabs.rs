@@ -1,13 +1,21 @@
 use tui::{
     backend::Backend,
     layout::Rect,
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Span, Spans},
     widgets::{Block, Tabs},
     Frame,
 };
 
-#[derive(Debug, PartialEq)]
+use std::str::FromStr;
+
+use crate::{
+    events::{Context, Event},
+    keymap::KeyBindings,
+    state::State,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TabsHeadings {
     AboutMe,
     ContactDetails,
@@ -15,6 +23,12 @@ pub enum TabsHeadings {
 }
 
 impl TabsHeadings {
+    pub const ALL: [TabsHeadings; 3] = [
+        TabsHeadings::AboutMe,
+        TabsHeadings::ContactDetails,
+        TabsHeadings::EmploymentAndEducation,
+    ];
+
     pub fn index(&self) -> usize {
         match self {
             TabsHeadings::AboutMe => 0,
@@ -23,28 +37,92 @@ impl TabsHeadings {
         }
     }
 
+    /// The name used for this tab in configuration, e.g. `initial_tab = "contact"`.
+    pub fn name(&self) -> &str {
+        match self {
+            TabsHeadings::AboutMe => "about",
+            TabsHeadings::ContactDetails => "contact",
+            TabsHeadings::EmploymentAndEducation => "employment",
+        }
+    }
+
     pub fn title(&self) -> &str {
         match self {
-            TabsHeadings::AboutMe => "About Me (a)",
-            TabsHeadings::ContactDetails => "Contact Details (c)",
-            TabsHeadings::EmploymentAndEducation => "Employment and Education (e)",
+            TabsHeadings::AboutMe => "About Me",
+            TabsHeadings::ContactDetails => "Contact Details",
+            TabsHeadings::EmploymentAndEducation => "Employment and Education",
+        }
+    }
+
+    /// The event that switches straight to this tab.
+    pub fn event(&self) -> Event {
+        match self {
+            TabsHeadings::AboutMe => Event::SwitchToAboutMe,
+            TabsHeadings::ContactDetails => Event::SwitchToContactInformation,
+            TabsHeadings::EmploymentAndEducation => Event::SwitchToEmployment,
         }
     }
 }
 
-pub fn render_tabs<B: Backend>(f: &mut Frame<B>, chunk: Rect, selected: usize) {
-    let titles = [
-        TabsHeadings::AboutMe.title(),
-        TabsHeadings::ContactDetails.title(),
-        TabsHeadings::EmploymentAndEducation.title(),
-        "Exit (q)",
-    ];
-    let tab_spans: Vec<Spans> = titles.iter().map(|t| Spans::from(Span::raw(*t))).collect();
+impl FromStr for TabsHeadings {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "about" => Ok(TabsHeadings::AboutMe),
+            "contact" => Ok(TabsHeadings::ContactDetails),
+            "employment" => Ok(TabsHeadings::EmploymentAndEducation),
+            _ => Err(format!(
+                "unknown tab {:?}, expected about, contact or employment",
+                s
+            )),
+        }
+    }
+}
+
+/// What the tab bar shows, in order: the tabs, then a last one for leaving,
+/// each with the first key bound to it, if any.
+fn titles(key_bindings: &KeyBindings) -> Vec<String> {
+    let bindings = key_bindings.bindings_for(Context::Global);
+    let title = |name: &str, event: Event| match bindings.iter().find(|(bound, _)| *bound == event)
+    {
+        Some((_, keys)) => format!("{} ({})", name, keys[0]),
+        None => name.to_string(),
+    };
+
+    let mut titles: Vec<String> = TabsHeadings::ALL
+        .iter()
+        .map(|tab| title(tab.title(), tab.event()))
+        .collect();
+    titles.push(title("Exit", Event::Quit));
+    titles
+}
+
+/// Which title in the tab bar drawn in `chunk` is at `column`, laid out the
+/// way `Tabs` does: a space either side of each title and a divider between.
+pub fn tab_at(chunk: Rect, column: u16, key_bindings: &KeyBindings) -> Option<usize> {
+    let mut x = chunk.left();
+    for (i, title) in titles(key_bindings).iter().enumerate() {
+        let start = x + 1;
+        let end = start + title.chars().count() as u16;
+        if (start..end).contains(&column) {
+            return Some(i);
+        }
+        x = end + 2;
+    }
+    None
+}
+
+pub fn render_tabs<B: Backend>(f: &mut Frame<B>, chunk: Rect, state: &State) {
+    let tab_spans: Vec<Spans> = titles(&state.key_bindings)
+        .into_iter()
+        .map(|t| Spans::from(Span::raw(t)))
+        .collect();
 
     let tabs = Tabs::new(tab_spans)
-        .select(selected)
+        .select(state.selected_tab.index())
         .block(Block::default())
-        .style(Style::default().fg(Color::Green))
+        .style(Style::default().fg(state.theme.accent()))
         .highlight_style(
             Style::default()
                 .add_modifier(Modifier::BOLD)
@@ -53,3 +131,46 @@ pub fn render_tabs<B: Backend>(f: &mut Frame<B>, chunk: Rect, selected: usize) {
 
     f.render_widget(tabs, chunk);
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_titles_follow_bindings() {
+        assert_eq!(
+            titles(&KeyBindings::default()),
+            vec![
+                "About Me (a)",
+                "Contact Details (c)",
+                "Employment and Education (e)",
+                "Exit (q)"
+            ]
+        );
+
+        let config = BTreeMap::from([
+            (String::from("quit"), vec![String::from("x")]),
+            (String::from("switch_to_contact_information"), vec![]),
+        ]);
+        let titles = titles(&KeyBindings::new(&config).unwrap());
+        assert_eq!(titles[1], "Contact Details");
+        assert_eq!(titles[3], "Exit (x)");
+    }
+
+    #[test]
+    fn test_tab_at() {
+        let key_bindings = KeyBindings::default();
+        let tab_at = |chunk, column| tab_at(chunk, column, &key_bindings);
+        let chunk = Rect::new(2, 11, 100, 3);
+        // " About Me (a) | Contact Details (c) | ..."
+        assert_eq!(tab_at(chunk, 2), None);
+        assert_eq!(tab_at(chunk, 3), Some(0));
+        assert_eq!(tab_at(chunk, 14), Some(0));
+        assert_eq!(tab_at(chunk, 15), None);
+        assert_eq!(tab_at(chunk, 18), Some(1));
+        assert_eq!(tab_at(chunk, 72), Some(3));
+        assert_eq!(tab_at(chunk, 99), None);
+    }
+}
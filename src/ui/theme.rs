@@ -0,0 +1,78 @@
+use std::{fmt, str::FromStr};
+
+use tui::style::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Theme {
+    Green,
+    Amber,
+    Blue,
+    Mono,
+}
+
+impl Theme {
+    pub fn accent(&self) -> Color {
+        match self {
+            Theme::Green => Color::Green,
+            Theme::Amber => Color::Yellow,
+            Theme::Blue => Color::Cyan,
+            Theme::Mono => Color::Reset,
+        }
+    }
+
+    pub fn focus(&self) -> Color {
+        match self {
+            Theme::Mono => Color::Reset,
+            _ => Color::White,
+        }
+    }
+
+    pub fn error(&self) -> Color {
+        match self {
+            Theme::Mono => Color::Reset,
+            _ => Color::Red,
+        }
+    }
+
+    /// The accent as a CSS colour, for exports that mirror the TUI.
+    pub fn css_color(&self) -> &str {
+        match self {
+            Theme::Green => "#3fb950",
+            Theme::Amber => "#d29922",
+            Theme::Blue => "#39c5cf",
+            Theme::Mono => "#e6e6e6",
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Theme::Green => "green",
+            Theme::Amber => "amber",
+            Theme::Blue => "blue",
+            Theme::Mono => "mono",
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "green" => Ok(Theme::Green),
+            "amber" => Ok(Theme::Amber),
+            "blue" => Ok(Theme::Blue),
+            "mono" => Ok(Theme::Mono),
+            _ => Err(format!(
+                "unknown theme {:?}, expected green, amber, blue or mono",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+use crate::source::Section;
+
+fn changed_section(path: &Path) -> Option<Section> {
+    let file_name = path.file_name()?.to_str()?;
+    Section::ALL
+        .into_iter()
+        .find(|section| section.key() == file_name)
+}
+
+/// Watches a local data directory, or a single resume document, and reports
+/// the sections whose files change so edits show up in a running TUI. The
+/// watcher stops when the returned `RecommendedWatcher` is dropped.
+pub fn watch(path: &Path) -> notify::Result<(RecommendedWatcher, UnboundedReceiver<Section>)> {
+    // Watch a document's directory rather than the file itself, since many
+    // editors save by replacing the file.
+    let (dir, document) = match path.is_file() {
+        true => (
+            path.parent().unwrap_or(Path::new(".")),
+            path.file_name().map(|name| name.to_os_string()),
+        ),
+        false => (path, None),
+    };
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let Ok(event) = result else {
+            return;
+        };
+        if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+            return;
+        }
+        for path in &event.paths {
+            let sections = match &document {
+                Some(document) if path.file_name() == Some(document.as_os_str()) => {
+                    Section::ALL.to_vec()
+                }
+                Some(_) => Vec::new(),
+                None => changed_section(path).into_iter().collect(),
+            };
+            for section in sections {
+                let _ = tx.send(section);
+            }
+        }
+    })?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    Ok((watcher, rx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_changed_section() {
+        assert_eq!(
+            changed_section(Path::new("data/about_me.json")),
+            Some(Section::AboutMe)
+        );
+        assert_eq!(
+            changed_section(Path::new("/tmp/employment.json")),
+            Some(Section::Employment)
+        );
+        assert_eq!(changed_section(Path::new("data/notes.txt")), None);
+    }
+
+    #[tokio::test]
+    async fn test_watch_reports_edited_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_watcher, mut changes) = watch(dir.path()).unwrap();
+
+        std::fs::write(dir.path().join("contact.json"), "{}").unwrap();
+
+        let section = tokio::time::timeout(Duration::from_secs(5), changes.recv())
+            .await
+            .unwrap();
+        assert_eq!(section, Some(Section::Contact));
+    }
+}